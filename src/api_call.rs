@@ -0,0 +1,96 @@
+use super::http_client::{HttpClient, AsyncHttpClient, RequestContext, HttpResponse, PixelaFuture};
+use super::error::PixelaClientError;
+use super::retry::RetryPolicy;
+
+use futures::{future, Future};
+use futures::future::Loop;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio_timer::Delay;
+
+type Parser<R> = Box<dyn Fn(&HttpResponse) -> Result<R, PixelaClientError> + Send + Sync>;
+
+/// A request to Pixela paired with the parser of its response.
+///
+/// It is shared by `PixelaClient` and `AsyncPixelaClient`, which only differ in how the request is sent.
+pub(crate) struct ApiCall<R> {
+    context: RequestContext,
+    parser: Parser<R>,
+    idempotent: bool,
+}
+
+impl<R> ApiCall<R> where R: Send + 'static {
+    pub fn new<F>(context: RequestContext, parser: F) -> Self where F: Fn(&HttpResponse) -> Result<R, PixelaClientError> + Send + Sync + 'static {
+        Self {
+            context,
+            parser: Box::new(parser),
+            idempotent: true,
+        }
+    }
+
+    /// Marks the call as not safe to send twice, so it is retried only when Pixela rejected it.
+    pub fn non_idempotent(mut self) -> Self {
+        self.idempotent = false;
+        self
+    }
+
+    pub fn send<C>(self, client: &C, policy: &RetryPolicy) -> Result<R, PixelaClientError> where C: HttpClient {
+        let mut attempt = 1;
+
+        loop {
+            let res = client.do_request(&self.context).and_then(|v| (self.parser)(&v));
+            match res {
+                Err(ref e) if policy.should_retry(attempt, e, self.idempotent) => {
+                    thread::sleep(policy.delay(attempt));
+                    attempt += 1;
+                },
+                res => return res,
+            }
+        }
+    }
+
+    pub fn send_async<C>(self, client: Arc<C>, policy: RetryPolicy) -> PixelaFuture<R> where C: AsyncHttpClient + Send + Sync + 'static {
+        let call = Arc::new(self);
+
+        let res = future::loop_fn(1, move |attempt| {
+            let call = call.clone();
+            let policy = policy.clone();
+
+            client.do_request(&call.context)
+                  .and_then({
+                      let call = call.clone();
+                      move |v| (call.parser)(&v)
+                  })
+                  .then(move |res| -> PixelaFuture<Loop<R, u32>> {
+                      match res {
+                          Err(ref e) if policy.should_retry(attempt, e, call.idempotent) => {
+                              let next = delay(policy.delay(attempt)).map(move |_| Loop::Continue(attempt + 1));
+                              Box::new(next)
+                          },
+                          res => Box::new(future::result(res.map(Loop::Break))),
+                      }
+                  })
+        });
+
+        Box::new(res)
+    }
+}
+
+fn delay(duration: Duration) -> PixelaFuture<()> {
+    if duration == Duration::from_millis(0) {
+        return Box::new(future::ok(()));
+    }
+
+    let res = Delay::new(Instant::now() + duration)
+        .map_err(PixelaClientError::TimerError);
+    Box::new(res)
+}
+
+/// Sends the call, or returns a failed future when the call could not be built.
+pub(crate) fn send_async<C, R>(client: &Arc<C>, policy: &RetryPolicy, call: Result<ApiCall<R>, PixelaClientError>) -> PixelaFuture<R> where C: AsyncHttpClient + Send + Sync + 'static, R: Send + 'static {
+    match call {
+        Ok(v) => v.send_async(client.clone(), policy.clone()),
+        Err(e) => Box::new(future::err(e)),
+    }
+}
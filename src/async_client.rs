@@ -0,0 +1,725 @@
+use super::{Authentication, PixelaClientBuilder, AsyncWebhookInvoker};
+use super::endpoint::Endpoint;
+use super::api_call::{ApiCall, send_async};
+use super::http_client::{AsyncHttpClient, TinyHttpClient, TinyAsyncHttpClient, PixelaFuture};
+use super::retry::RetryPolicy;
+use super::stopwatch::LocalStopwatch;
+use super::error::PixelaClientError;
+use super::user::{self, ConsentAnswer, CreateUserParam, ProfileUpdate};
+use super::graph::{self, Graph, GraphType, GraphUpdate, GraphStats, GraphTypes, SvgOptions, GraphHtmlMode, GraphUrls};
+use super::pixel::{self, Pixel, TodayPixel, PixelList, PixelBatchResult};
+use super::channel::{self, Channel};
+use super::notification::{self, NotificationRule};
+use super::webhook::{self, Webhook, WebhookType};
+use super::types::{Username, Token, GraphId, PixelDate, Quantity, ChannelId, NotificationId, WebhookHash};
+
+use futures::{future, stream, Future, Stream};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
+
+/// An asynchronous `PixelaClient` to request to Pixela with.
+///
+/// Every method returns a future instead of blocking on the response.
+/// With the default `TinyAsyncHttpClient`, the futures must be run on a tokio runtime.
+///
+/// Quantities are checked against the type of the target graph before they are sent.
+/// The client learns the types from `get_graphs`, which it calls before the first write to a graph it does not know yet.
+/// A graph is looked up once until the next `get_graphs`, even when the lookup fails.
+/// Quantities read from Pixela are converted to the type of the graph when the client already knows it.
+pub struct AsyncPixelaClient<C = TinyAsyncHttpClient> {
+    pub(crate) auth: Authentication,
+    pub(crate) endpoint: Endpoint,
+    pub(crate) http_client: Arc<C>,
+    pub(crate) retry_policy: RetryPolicy,
+    pub(crate) graph_types: Arc<GraphTypes>,
+}
+
+impl AsyncPixelaClient<TinyAsyncHttpClient> {
+    /// Constructs a new `AsyncPixelaClient`.
+    ///
+    /// This method does not verify authentication.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when the username or the token violates the validation rule of Pixela,
+    /// or when the `TinyAsyncHttpClient` cannot be built.
+    pub fn new(username: &str, token: &str) -> Result<Self, PixelaClientError> {
+        AsyncPixelaClient::builder(username, token).build_async()
+    }
+
+    /// Creates a `PixelaClientBuilder` to configure an `AsyncPixelaClient`.
+    pub fn builder(username: &str, token: &str) -> PixelaClientBuilder<TinyAsyncHttpClient> {
+        PixelaClientBuilder::new(username, token).default_http_client(|| TinyHttpClient::builder().build_async())
+    }
+
+    /// Create a new Pixela user.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when the username or the token is invalid or request not success in Pixela.
+    pub fn create_new_user(username: &str, token: &str, agree_terms_of_service: ConsentAnswer, not_minor: ConsentAnswer) -> PixelaFuture<()> {
+        match AsyncPixelaClient::new(username, token) {
+            Ok(v) => v.create_user(agree_terms_of_service, not_minor),
+            Err(e) => Box::new(future::err(e)),
+        }
+    }
+
+    /// Invoke a webhook of the user without the token, for example from a CI pipeline.
+    /// Use `AsyncWebhookInvoker` to invoke webhooks on another server or with another transport.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when the username is invalid or request not success in Pixela.
+    pub fn invoke_user_webhook(username: &str, webhook_hash: &WebhookHash) -> PixelaFuture<()> {
+        match AsyncWebhookInvoker::builder(username).build_async() {
+            Ok(v) => v.invoke(webhook_hash),
+            Err(e) => Box::new(future::err(e)),
+        }
+    }
+}
+
+impl<C> AsyncPixelaClient<C> where C: AsyncHttpClient + Send + Sync + 'static {
+    /// Constructs a new `AsyncPixelaClient` which sends requests with the given `AsyncHttpClient`.
+    ///
+    /// This method does not verify authentication.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when the username or the token violates the validation rule of Pixela.
+    pub fn with_http_client(username: &str, token: &str, http_client: C) -> Result<Self, PixelaClientError> {
+        PixelaClientBuilder::new(username, token).http_client(http_client).build_async()
+    }
+
+    /// Create a new Pixela user with the username and token of this client.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when request not success in Pixela.
+    pub fn create_user(&self, agree_terms_of_service: ConsentAnswer, not_minor: ConsentAnswer) -> PixelaFuture<()> {
+        let param = CreateUserParam {
+            username: self.auth.username.to_owned(),
+            token: self.auth.token.to_owned(),
+            agree_terms_of_service,
+            not_minor
+        };
+
+        send_async(&self.http_client, &self.retry_policy, user::create(&self.endpoint, &param))
+    }
+
+    /// Updates the authentication token for the specified user.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when request not success in Pixela.
+    pub fn update_user_token(&self, new_token: &Token) -> PixelaFuture<()> {
+        send_async(&self.http_client, &self.retry_policy, user::update(&self.endpoint, &self.auth, new_token))
+    }
+
+    /// Deletes the specified registered user.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when request not success in Pixela.
+    pub fn delete_user(&self) -> PixelaFuture<()> {
+        send_async(&self.http_client, &self.retry_policy, user::delete(&self.endpoint, &self.auth))
+    }
+
+    /// Update the public profile page of the user.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when request not success in Pixela.
+    pub fn update_profile(&self, update: &ProfileUpdate) -> PixelaFuture<()> {
+        send_async(&self.http_client, &self.retry_policy, user::update_profile(&self.endpoint, &self.auth, update))
+    }
+
+    /// Get the public profile page of any user as HTML.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when request not success in Pixela.
+    pub fn get_profile_page(&self, username: &Username) -> PixelaFuture<String> {
+        send_async(&self.http_client, &self.retry_policy, user::get_profile_page(&self.endpoint, username))
+    }
+
+    /// Create a new pixelation graph definition.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when request not success in Pixela.
+    pub fn create_graph(&self, graph: &Graph) -> PixelaFuture<()> {
+        let graph_types = self.graph_types.clone();
+        let created = graph.to_owned();
+
+        let res = send_async(&self.http_client, &self.retry_policy, graph::create(&self.endpoint, &self.auth, graph))
+            .map(move |_| graph_types.insert(&created));
+        Box::new(res)
+    }
+
+    /// Get the pixelation graph definition.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when request not success in Pixela.
+    pub fn get_graph(&self, graph_id: &GraphId) -> PixelaFuture<Graph> {
+        let graph_types = self.graph_types.clone();
+
+        let res = send_async(&self.http_client, &self.retry_policy, graph::get(&self.endpoint, &self.auth, graph_id))
+            .map(move |graph| {
+                graph_types.insert(&graph);
+                graph
+            });
+        Box::new(res)
+    }
+
+    /// Get all predefined pixelation graph definitions.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when request not success in Pixela.
+    pub fn get_graphs(&self) -> PixelaFuture<Vec<Graph>> {
+        let graph_types = self.graph_types.clone();
+
+        let res = send_async(&self.http_client, &self.retry_policy, graph::get_all(&self.endpoint, &self.auth))
+            .map(move |graphs| {
+                graph_types.reset(&graphs);
+                graphs
+            });
+        Box::new(res)
+    }
+
+    /// Get the statistics of the graph, such as the total, the maximum and the average of the quantities.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when request not success in Pixela.
+    pub fn get_graph_stats(&self, graph_id: &GraphId) -> PixelaFuture<GraphStats> {
+        send_async(&self.http_client, &self.retry_policy, graph::get_stats(&self.endpoint, &self.auth, graph_id, self.graph_types.get(graph_id)))
+    }
+
+    /// Based on the registered information, express the graph in SVG format diagram.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when request not success in Pixela.
+    pub fn get_graph_svg(&self, graph_id: &GraphId, options: &SvgOptions) -> PixelaFuture<String> {
+        send_async(&self.http_client, &self.retry_policy, graph::get_graph_svg(&self.endpoint, &self.auth, graph_id, options))
+    }
+
+    /// Get the detail page of the graph as HTML.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when request not success in Pixela.
+    pub fn get_graph_html(&self, graph_id: &GraphId, mode: GraphHtmlMode) -> PixelaFuture<String> {
+        send_async(&self.http_client, &self.retry_policy, graph::get_graph_html(&self.endpoint, &self.auth, graph_id, mode))
+    }
+
+    /// Returns the public URLs of the graph without sending a request.
+    pub fn graph_url(&self, graph_id: &GraphId) -> GraphUrls {
+        graph::urls(&self.endpoint, &self.auth, graph_id)
+    }
+
+    /// Update predefined pixelation graph definitions. Only the fields set in the `GraphUpdate` are changed.
+    /// Use `GraphUpdate::from(&graph)` to send every updatable field of a `Graph`.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when request not success in Pixela.
+    pub fn update_graph(&self, graph_id: &GraphId, update: &GraphUpdate) -> PixelaFuture<()> {
+        let graph_types = self.graph_types.clone();
+        let id = graph_id.to_owned();
+
+        let res = send_async(&self.http_client, &self.retry_policy, graph::update(&self.endpoint, &self.auth, graph_id, update))
+            .map(move |_| graph_types.remove(&id));
+        Box::new(res)
+    }
+
+    /// Make Pixela purge the caches of the graph at its purge cache URLs, for example after `record_pixels`.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when request not success in Pixela.
+    pub fn purge_graph_cache(&self, graph_id: &GraphId) -> PixelaFuture<()> {
+        send_async(&self.http_client, &self.retry_policy, graph::purge_cache(&self.endpoint, &self.auth, graph_id))
+    }
+
+    /// Delete the predefined pixelation graph definition.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when request not success in Pixela.
+    pub fn delete_graph(&self, graph_id: &GraphId) -> PixelaFuture<()> {
+        let graph_types = self.graph_types.clone();
+        let graph_id = graph_id.to_owned();
+
+        let res = send_async(&self.http_client, &self.retry_policy, graph::delete(&self.endpoint, &self.auth, &graph_id))
+            .map(move |_| graph_types.remove(&graph_id));
+        Box::new(res)
+    }
+
+    /// It records the quantity of the specified date as a "Pixel".
+    ///
+    /// # Errors
+    ///
+    /// The future fails when the quantity does not match the graph type or request not success in Pixela.
+    pub fn record_pixel(&self, graph_id: &GraphId, date: &PixelDate, quantity: &Quantity) -> PixelaFuture<()> {
+        self.create_pixel(graph_id, Ok(Pixel::new(date, quantity)))
+    }
+
+    /// It records the quantity of the specified date as a "Pixel" with the data serialized into its optional data.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when the JSON of the data is larger than 10KB, the quantity does not match the graph type or request not success in Pixela.
+    pub fn record_pixel_with_data<D>(&self, graph_id: &GraphId, date: &PixelDate, quantity: &Quantity, data: &D) -> PixelaFuture<()> where D: Serialize {
+        self.create_pixel(graph_id, Pixel::with_optional_data(date, quantity, data))
+    }
+
+    /// It records the pixels at once, sending them in chunks of `MAX_PIXELS_PER_BATCH` pixels one after another.
+    /// A failed chunk does not stop the following chunks, and the result of every chunk is returned in order.
+    ///
+    /// # Errors
+    ///
+    /// Failures of the chunks are reported in their results, so the future itself does not fail.
+    /// Nothing is sent when `pixels` is empty.
+    pub fn record_pixels(&self, graph_id: &GraphId, pixels: &[Pixel]) -> PixelaFuture<Vec<PixelBatchResult>> {
+        if pixels.is_empty() {
+            return Box::new(future::ok(Vec::new()));
+        }
+
+        let endpoint = self.endpoint.clone();
+        let auth = self.auth.clone();
+        let http_client = self.http_client.clone();
+        let retry_policy = self.retry_policy.clone();
+        let graph_id = graph_id.to_owned();
+        let pixels = pixels.to_vec();
+
+        let res = self.graph_type(&graph_id).and_then(move |graph_type| {
+            let calls: Vec<_> = pixel::batch_ranges(pixels.len()).into_iter()
+                .map(|range| {
+                    let call = pixel::create_batch(&endpoint, &auth, &graph_id, &pixels[range.clone()], graph_type);
+                    (range, call)
+                })
+                .collect();
+
+            stream::iter_ok(calls)
+                .and_then(move |(range, call)| {
+                    send_async(&http_client, &retry_policy, call).then(move |result| Ok(PixelBatchResult { range, result }))
+                })
+                .collect()
+        });
+        Box::new(res)
+    }
+
+    /// Get registered quantity as "Pixel". The quantity has the type of the graph when the client knows it.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when request not success in Pixela.
+    pub fn get_pixel(&self, graph_id: &GraphId, date: &PixelDate) -> PixelaFuture<Pixel> {
+        send_async(&self.http_client, &self.retry_policy, pixel::get(&self.endpoint, &self.auth, graph_id, date, self.graph_types.get(graph_id)))
+    }
+
+    /// Get registered quantity as "Pixel" with its optional data deserialized into `D`.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when the optional data can not be deserialized into `D` or request not success in Pixela.
+    pub fn get_pixel_with_data<D>(&self, graph_id: &GraphId, date: &PixelDate) -> PixelaFuture<(Pixel, Option<D>)> where D: DeserializeOwned + Send + 'static {
+        let res = self.get_pixel(graph_id, date).and_then(|pixel| {
+            let data = pixel.optional_data()?;
+            Ok((pixel, data))
+        });
+        Box::new(res)
+    }
+
+    /// Get the latest recorded pixel of the graph, or `None` when the graph has no pixel.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when request not success in Pixela.
+    pub fn get_latest_pixel(&self, graph_id: &GraphId) -> PixelaFuture<Option<Pixel>> {
+        send_async(&self.http_client, &self.retry_policy, pixel::get_latest(&self.endpoint, &self.auth, graph_id, self.graph_types.get(graph_id)))
+    }
+
+    /// Get the pixel of today in the timezone of the graph, or `None` when nothing is recorded today.
+    /// With `return_empty`, Pixela answers an empty day with a zero quantity instead of an error.
+    /// Pixela does not return the date of the pixel.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when request not success in Pixela.
+    pub fn get_today_pixel(&self, graph_id: &GraphId, return_empty: bool) -> PixelaFuture<Option<TodayPixel>> {
+        send_async(&self.http_client, &self.retry_policy, pixel::get_today(&self.endpoint, &self.auth, graph_id, return_empty, self.graph_types.get(graph_id)))
+    }
+
+    /// Get the pixels recorded between `from` and `to`, which Pixela limits to a year.
+    /// Only the dates are returned unless `with_body` is set, in which case the quantities and optional data are included.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when request not success in Pixela.
+    pub fn list_pixels(&self, graph_id: &GraphId, from: Option<&PixelDate>, to: Option<&PixelDate>, with_body: bool) -> PixelaFuture<PixelList> {
+        let graph_type = if with_body { self.graph_types.get(graph_id) } else { None };
+        send_async(&self.http_client, &self.retry_policy, pixel::list(&self.endpoint, &self.auth, graph_id, from, to, with_body, graph_type))
+    }
+
+    /// Update the quantity already registered as a "Pixel".
+    ///
+    /// # Errors
+    ///
+    /// The future fails when the quantity does not match the graph type or request not success in Pixela.
+    pub fn update_pixel(&self, graph_id: &GraphId, date: &PixelDate, quantity: &Quantity) -> PixelaFuture<()> {
+        self.put_pixel(graph_id, Ok(Pixel::new(date, quantity)))
+    }
+
+    /// Update the quantity and the optional data already registered as a "Pixel".
+    ///
+    /// # Errors
+    ///
+    /// The future fails when the JSON of the data is larger than 10KB, the quantity does not match the graph type or request not success in Pixela.
+    pub fn update_pixel_with_data<D>(&self, graph_id: &GraphId, date: &PixelDate, quantity: &Quantity, data: &D) -> PixelaFuture<()> where D: Serialize {
+        self.put_pixel(graph_id, Pixel::with_optional_data(date, quantity, data))
+    }
+
+    /// Delete the registered "Pixel".
+    ///
+    /// # Errors
+    ///
+    /// The future fails when request not success in Pixela.
+    pub fn delete_pixel(&self, graph_id: &GraphId, date: &PixelDate) -> PixelaFuture<()> {
+        send_async(&self.http_client, &self.retry_policy, pixel::delete(&self.endpoint, &self.auth, graph_id, date))
+    }
+
+    /// Add the quantity to "Pixel" of the day in the timezone of the graph.
+    /// It is never retried unless Pixela rejected it, so that the quantity is not added twice.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when the quantity does not match the graph type or request not success in Pixela.
+    pub fn add(&self, graph_id: &GraphId, quantity: &Quantity) -> PixelaFuture<()> {
+        let id = graph_id.to_owned();
+        let quantity = *quantity;
+        self.with_graph_type(graph_id, move |endpoint, auth, graph_type| pixel::add(endpoint, auth, &id, &quantity, graph_type))
+    }
+
+    /// Subtract the quantity from "Pixel" of the day in the timezone of the graph.
+    /// It is never retried unless Pixela rejected it, so that the quantity is not subtracted twice.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when the quantity does not match the graph type or request not success in Pixela.
+    pub fn subtract(&self, graph_id: &GraphId, quantity: &Quantity) -> PixelaFuture<()> {
+        let id = graph_id.to_owned();
+        let quantity = *quantity;
+        self.with_graph_type(graph_id, move |endpoint, auth, graph_type| pixel::subtract(endpoint, auth, &id, &quantity, graph_type))
+    }
+
+    /// Starts the stopwatch of the graph in Pixela when it is not running, or stops it otherwise,
+    /// which records the elapsed minutes to the pixel of the day.
+    /// Pixela does not tell whether the stopwatch is running.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when request not success in Pixela.
+    pub fn toggle_stopwatch(&self, graph_id: &GraphId) -> PixelaFuture<()> {
+        send_async(&self.http_client, &self.retry_policy, pixel::stopwatch(&self.endpoint, &self.auth, graph_id))
+    }
+
+    /// Toggles the stopwatch of the graph in Pixela, and starts the `LocalStopwatch` when Pixela is unavailable.
+    /// The stopwatch in Pixela is stopped instead when it is already running.
+    /// After a timeout or a server error Pixela may have toggled the stopwatch anyway,
+    /// so the minutes can be recorded by both stopwatches. Check the pixel of the day in that case.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when request not success in Pixela for other reasons, or the `LocalStopwatch` can not be started.
+    pub fn toggle_stopwatch_or_start_local(&self, graph_id: &GraphId, local: &LocalStopwatch) -> PixelaFuture<()> {
+        let local = local.to_owned();
+
+        let res = self.toggle_stopwatch(graph_id).or_else(move |e| {
+            if e.is_unavailable() {
+                return local.start();
+            }
+            Err(e)
+        });
+        Box::new(res)
+    }
+
+    /// Stops the `LocalStopwatch` and records the elapsed minutes with `add` when it is running,
+    /// or toggles the stopwatch of the graph in Pixela otherwise.
+    /// The `LocalStopwatch` keeps running when the minutes could not be recorded, so that they are not lost.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when request not success in Pixela or the `LocalStopwatch` can not be read.
+    pub fn stop_local_or_toggle_stopwatch(&self, graph_id: &GraphId, local: &LocalStopwatch) -> PixelaFuture<()> {
+        let minutes = match local.elapsed_minutes() {
+            Ok(v) => v,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        match minutes {
+            None => self.toggle_stopwatch(graph_id),
+            Some(0) => Box::new(future::result(local.reset())),
+            Some(minutes) => {
+                let local = local.to_owned();
+                Box::new(self.add(graph_id, &Quantity::Int(minutes as i64)).and_then(move |_| local.reset()))
+            },
+        }
+    }
+
+    /// Increment quantity "Pixel" of the day (UTC).
+    /// If the graph type is int then 1 added, and for float then 0.01 added.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when request not success in Pixela.
+    pub fn increment(&self, graph_id: &GraphId) -> PixelaFuture<()> {
+        send_async(&self.http_client, &self.retry_policy, pixel::increment(&self.endpoint, &self.auth, graph_id))
+    }
+
+    /// Decrement quantity "Pixel" of the day (UTC).
+    /// If the graph type is int then -1 added, and for float then -0.01 added.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when request not success in Pixela.
+    pub fn decrement(&self, graph_id: &GraphId) -> PixelaFuture<()> {
+        send_async(&self.http_client, &self.retry_policy, pixel::decrement(&self.endpoint, &self.auth, graph_id))
+    }
+
+    /// Create a notification channel.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when request not success in Pixela.
+    pub fn create_channel(&self, channel: &Channel) -> PixelaFuture<()> {
+        send_async(&self.http_client, &self.retry_policy, channel::create(&self.endpoint, &self.auth, channel))
+    }
+
+    /// Get all notification channels of the user.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when request not success in Pixela.
+    pub fn get_channels(&self) -> PixelaFuture<Vec<Channel>> {
+        send_async(&self.http_client, &self.retry_policy, channel::get_all(&self.endpoint, &self.auth))
+    }
+
+    /// Update the name and the settings of the channel with the same id.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when request not success in Pixela.
+    pub fn update_channel(&self, channel: &Channel) -> PixelaFuture<()> {
+        send_async(&self.http_client, &self.retry_policy, channel::update(&self.endpoint, &self.auth, channel))
+    }
+
+    /// Delete a notification channel.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when request not success in Pixela.
+    pub fn delete_channel(&self, channel_id: &ChannelId) -> PixelaFuture<()> {
+        send_async(&self.http_client, &self.retry_policy, channel::delete(&self.endpoint, &self.auth, channel_id))
+    }
+
+    /// Create a notification rule of the graph. The threshold has the type of the graph.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when the threshold does not match the type of the graph or request not success in Pixela.
+    pub fn create_notification(&self, graph_id: &GraphId, rule: &NotificationRule) -> PixelaFuture<()> {
+        let id = graph_id.to_owned();
+        let rule = rule.to_owned();
+        self.with_graph_type(graph_id, move |endpoint, auth, graph_type| notification::create(endpoint, auth, &id, &rule, graph_type))
+    }
+
+    /// Get all notification rules of the graph.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when request not success in Pixela.
+    pub fn get_notifications(&self, graph_id: &GraphId) -> PixelaFuture<Vec<NotificationRule>> {
+        send_async(&self.http_client, &self.retry_policy, notification::get_all(&self.endpoint, &self.auth, graph_id))
+    }
+
+    /// Update the notification rule of the graph with the same id.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when the threshold does not match the type of the graph or request not success in Pixela.
+    pub fn update_notification(&self, graph_id: &GraphId, rule: &NotificationRule) -> PixelaFuture<()> {
+        let id = graph_id.to_owned();
+        let rule = rule.to_owned();
+        self.with_graph_type(graph_id, move |endpoint, auth, graph_type| notification::update(endpoint, auth, &id, &rule, graph_type))
+    }
+
+    /// Delete a notification rule of the graph.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when request not success in Pixela.
+    pub fn delete_notification(&self, graph_id: &GraphId, notification_id: &NotificationId) -> PixelaFuture<()> {
+        send_async(&self.http_client, &self.retry_policy, notification::delete(&self.endpoint, &self.auth, graph_id, notification_id))
+    }
+
+    /// Create a webhook which updates the graph when it is invoked, and return its hash.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when request not success in Pixela.
+    pub fn create_webhook(&self, graph_id: &GraphId, webhook_type: WebhookType) -> PixelaFuture<WebhookHash> {
+        send_async(&self.http_client, &self.retry_policy, webhook::create(&self.endpoint, &self.auth, graph_id, webhook_type))
+    }
+
+    /// Get all webhooks of the user.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when request not success in Pixela.
+    pub fn get_webhooks(&self) -> PixelaFuture<Vec<Webhook>> {
+        send_async(&self.http_client, &self.retry_policy, webhook::get_all(&self.endpoint, &self.auth))
+    }
+
+    /// Invoke a webhook of the user. The token is not sent.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when request not success in Pixela.
+    pub fn invoke_webhook(&self, webhook_hash: &WebhookHash) -> PixelaFuture<()> {
+        send_async(&self.http_client, &self.retry_policy, webhook::invoke(&self.endpoint, &self.auth.username, webhook_hash))
+    }
+
+    /// Delete a webhook.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when request not success in Pixela.
+    pub fn delete_webhook(&self, webhook_hash: &WebhookHash) -> PixelaFuture<()> {
+        send_async(&self.http_client, &self.retry_policy, webhook::delete(&self.endpoint, &self.auth, webhook_hash))
+    }
+
+    fn create_pixel(&self, graph_id: &GraphId, param: Result<Pixel, PixelaClientError>) -> PixelaFuture<()> {
+        let param = match param {
+            Ok(v) => v,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        let id = graph_id.to_owned();
+        self.with_graph_type(graph_id, move |endpoint, auth, graph_type| pixel::create(endpoint, auth, &id, &param, graph_type))
+    }
+
+    fn put_pixel(&self, graph_id: &GraphId, param: Result<Pixel, PixelaClientError>) -> PixelaFuture<()> {
+        let param = match param {
+            Ok(v) => v,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        let id = graph_id.to_owned();
+        self.with_graph_type(graph_id, move |endpoint, auth, graph_type| pixel::update(endpoint, auth, &id, &param, graph_type))
+    }
+
+    /// Returns the type of the graph to write to, fetching the graph definitions when it is not known yet.
+    /// It is `None` when the graph does not exist or the definitions can not be fetched, leaving Pixela to validate the quantity.
+    /// Either way, the graph is not looked up again until the next `get_graphs`. The future never fails.
+    fn graph_type(&self, graph_id: &GraphId) -> PixelaFuture<Option<GraphType>> {
+        if let Some(v) = self.graph_types.lookup(graph_id) {
+            return Box::new(future::ok(v));
+        }
+
+        let graph_types = self.graph_types.clone();
+        let graph_id = graph_id.to_owned();
+        Box::new(self.get_graphs().then(move |_| {
+            graph_types.insert_missing(&graph_id);
+            Ok(graph_types.get(&graph_id))
+        }))
+    }
+
+    /// Builds the call once the type of the graph is known, and sends it.
+    fn with_graph_type<R, F>(&self, graph_id: &GraphId, build: F) -> PixelaFuture<R>
+        where R: Send + 'static,
+              F: FnOnce(&Endpoint, &Authentication, Option<GraphType>) -> Result<ApiCall<R>, PixelaClientError> + Send + 'static {
+        let endpoint = self.endpoint.clone();
+        let auth = self.auth.clone();
+        let http_client = self.http_client.clone();
+        let retry_policy = self.retry_policy.clone();
+
+        let res = self.graph_type(graph_id).and_then(move |graph_type| {
+            send_async(&http_client, &retry_policy, build(&endpoint, &auth, graph_type))
+        });
+        Box::new(res)
+    }
+}
+
+#[cfg(test)]
+mod async_client_test {
+    use super::*;
+    use super::super::{RequestContext, HttpResponse};
+    use futures::{future, Future};
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    /// Returns the responses in order and repeats the last one.
+    struct RecordingAsyncHttpClient {
+        uris: Mutex<Vec<String>>,
+        responses: Mutex<Vec<HttpResponse>>,
+    }
+
+    impl RecordingAsyncHttpClient {
+        fn new(responses: Vec<HttpResponse>) -> Self {
+            Self {
+                uris: Mutex::new(Vec::new()),
+                responses: Mutex::new(responses),
+            }
+        }
+    }
+
+    impl AsyncHttpClient for RecordingAsyncHttpClient {
+        fn do_request(&self, context: &RequestContext) -> PixelaFuture<HttpResponse> {
+            self.uris.lock().unwrap().push(context.url().unwrap());
+
+            let mut responses = self.responses.lock().unwrap();
+            if responses.len() > 1 {
+                return Box::new(future::ok(responses.remove(0)));
+            }
+            Box::new(future::ok(responses[0].clone()))
+        }
+    }
+
+    fn test_client(responses: Vec<HttpResponse>) -> AsyncPixelaClient<RecordingAsyncHttpClient> {
+        PixelaClientBuilder::new("testuser", "testtoken")
+            .http_client(RecordingAsyncHttpClient::new(responses))
+            .retry_policy(RetryPolicy::new(3).base_delay(Duration::from_millis(0)))
+            .build_async()
+            .unwrap()
+    }
+
+    #[test]
+    fn get_pixel_test() {
+        let graphs = HttpResponse::new(200, r#"{"graphs":[{"id":"testid","name":"test","unit":"kg","type":"float","color":"shibafu"}]}"#);
+        let client = test_client(vec![graphs, HttpResponse::new(200, r#"{"quantity":"5"}"#)]);
+        client.get_graphs().wait().unwrap();
+        let pixel = client.get_pixel(&GraphId::new("testid").unwrap(), &PixelDate::new("20181018").unwrap()).wait().unwrap();
+
+        assert_eq!(pixel.date.as_str(), "20181018");
+        assert_eq!(pixel.quantity, Quantity::Float(5.0));
+        assert_eq!(*client.http_client.uris.lock().unwrap(), vec![
+            "https://pixe.la/v1/users/testuser/graphs",
+            "https://pixe.la/v1/users/testuser/graphs/testid/20181018",
+        ]);
+    }
+
+    #[test]
+    fn retry_rejected_request_test() {
+        let rejected = HttpResponse::new(503, r#"{"message":"Please retry this request.","isSuccess":false,"isRejected":true}"#);
+        let success = HttpResponse::new(200, r#"{"message":"Success.","isSuccess":true}"#);
+        let client = test_client(vec![rejected, success]);
+
+        client.increment(&GraphId::new("testid").unwrap()).wait().unwrap();
+        assert_eq!(client.http_client.uris.lock().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,173 @@
+use super::Authentication;
+use super::endpoint::Endpoint;
+use super::response;
+use super::api_call::ApiCall;
+use super::http_client::{RequestContext, HttpResponse, MethodType};
+use super::error::PixelaClientError;
+use super::types::ChannelId;
+
+use serde_json;
+
+/// A notification channel in Pixela, to which the notification rules of graphs are sent.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Channel {
+    /// It is an ID for identifying the channel.
+    pub id: ChannelId,
+    /// It is the name of the channel.
+    pub name: String,
+    /// Where the notifications are sent, serialized as the `type` and `detail` of the channel.
+    #[serde(flatten)]
+    pub detail: ChannelDetail,
+}
+
+impl Channel {
+    /// Constructs a new `Channel`.
+    pub fn new(id: &ChannelId, name: &str, detail: ChannelDetail) -> Self {
+        Self {
+            id: id.to_owned(),
+            name: name.to_owned(),
+            detail,
+        }
+    }
+}
+
+/// The type of a channel with its settings.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", content = "detail")]
+pub enum ChannelDetail {
+    /// Notifications are posted to a Slack incoming webhook.
+    #[serde(rename = "slack")]
+    Slack(SlackDetail),
+}
+
+/// Settings of a Slack channel.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SlackDetail {
+    /// The incoming webhook URL of Slack.
+    pub url: String,
+    /// The name shown as the sender of the notifications.
+    #[serde(rename = "userName")]
+    pub user_name: String,
+    /// The name of the Slack channel, which is shown by Pixela only.
+    #[serde(rename = "channelName")]
+    pub channel_name: String,
+}
+
+impl SlackDetail {
+    /// Constructs a new `SlackDetail`.
+    pub fn new(url: &str, user_name: &str, channel_name: &str) -> Self {
+        Self {
+            url: url.to_owned(),
+            user_name: user_name.to_owned(),
+            channel_name: channel_name.to_owned(),
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct ChannelUpdate<'a> {
+    name: &'a str,
+    #[serde(flatten)]
+    detail: &'a ChannelDetail,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct Channels {
+    channels: Vec<Channel>,
+}
+
+pub(crate) fn create(endpoint: &Endpoint, auth: &Authentication, channel: &Channel) -> Result<ApiCall<()>, PixelaClientError> {
+    let uri = endpoint.channels(auth.username.as_str());
+    let body = serde_json::to_string(channel)?;
+    let context = RequestContext::new(
+        &uri,
+        MethodType::Post,
+        Some(&body),
+        Some(auth.token.as_str()),
+    );
+
+    Ok(ApiCall::new(context, response::build_result).non_idempotent())
+}
+
+pub(crate) fn get_all(endpoint: &Endpoint, auth: &Authentication) -> Result<ApiCall<Vec<Channel>>, PixelaClientError> {
+    let uri = endpoint.channels(auth.username.as_str());
+    let context = RequestContext::new(
+        &uri,
+        MethodType::Get,
+        None,
+        Some(auth.token.as_str()),
+    );
+
+    Ok(ApiCall::new(context, parse_channels))
+}
+
+fn parse_channels(response: &HttpResponse) -> Result<Vec<Channel>, PixelaClientError> {
+    response::check_error(response)?;
+
+    let res: Channels = serde_json::from_str(&response.body)?;
+    Ok(res.channels)
+}
+
+/// Replaces the name and the settings of the channel identified by its id.
+pub(crate) fn update(endpoint: &Endpoint, auth: &Authentication, channel: &Channel) -> Result<ApiCall<()>, PixelaClientError> {
+    let param = ChannelUpdate {
+        name: &channel.name,
+        detail: &channel.detail,
+    };
+
+    let uri = endpoint.channel(auth.username.as_str(), channel.id.as_str());
+    let body = serde_json::to_string(&param)?;
+    let context = RequestContext::new(
+        &uri,
+        MethodType::Put,
+        Some(&body),
+        Some(auth.token.as_str()),
+    );
+
+    Ok(ApiCall::new(context, response::build_result))
+}
+
+pub(crate) fn delete(endpoint: &Endpoint, auth: &Authentication, channel_id: &ChannelId) -> Result<ApiCall<()>, PixelaClientError> {
+    let uri = endpoint.channel(auth.username.as_str(), channel_id.as_str());
+    let context = RequestContext::new(
+        &uri,
+        MethodType::Delete,
+        None,
+        Some(auth.token.as_str()),
+    );
+
+    Ok(ApiCall::new(context, response::build_result).non_idempotent())
+}
+
+#[cfg(test)]
+mod channel_test {
+    use super::*;
+
+    fn test_channel() -> Channel {
+        let detail = SlackDetail::new("https://hooks.slack.com/services/xxxx", "pixela-bot", "habits");
+        Channel::new(&ChannelId::new("testchannel").unwrap(), "test", ChannelDetail::Slack(detail))
+    }
+
+    #[test]
+    fn channel_serialize_test() {
+        let json = serde_json::to_string(&test_channel()).unwrap();
+        assert_eq!(json, r#"{"id":"testchannel","name":"test","type":"slack","detail":{"url":"https://hooks.slack.com/services/xxxx","userName":"pixela-bot","channelName":"habits"}}"#);
+    }
+
+    #[test]
+    fn channels_deserialize_test() {
+        let json = r#"{"channels":[{"id":"testchannel","name":"test","type":"slack","detail":{"url":"https://hooks.slack.com/services/xxxx","userName":"pixela-bot","channelName":"habits"}}]}"#;
+
+        let res = parse_channels(&HttpResponse::new(200, json)).unwrap();
+        assert_eq!(res, vec![test_channel()]);
+    }
+
+    #[test]
+    fn channel_update_serialize_test() {
+        let channel = test_channel();
+        let param = ChannelUpdate { name: &channel.name, detail: &channel.detail };
+
+        let json = serde_json::to_string(&param).unwrap();
+        assert_eq!(json, r#"{"name":"test","type":"slack","detail":{"url":"https://hooks.slack.com/services/xxxx","userName":"pixela-bot","channelName":"habits"}}"#);
+    }
+}
@@ -1,65 +1,219 @@
-const BASE_URL: &str = "https://pixe.la/v1/";
+pub const DEFAULT_BASE_URL: &str = "https://pixe.la";
+pub const DEFAULT_API_VERSION: &str = "v1";
 
-pub fn users() -> String {
-    String::from(format!("{}users", BASE_URL))
+/// Builds the Pixela API urls from a base url and an api version prefix.
+#[derive(Debug, Clone)]
+pub struct Endpoint {
+    base_url: String,
+    api_url: String,
 }
 
-pub fn user(username: &str) -> String {
-    String::from(format!("{}users/{}", BASE_URL, username))
-}
+impl Endpoint {
+    pub fn new(base_url: &str, api_version: &str) -> Self {
+        let base_url = base_url.trim_end_matches('/');
+        let api_version = api_version.trim_matches('/');
 
-pub fn graphs(username: &str) -> String {
-    let user_url = user(username);
-    String::from(format!("{}/graphs", user_url))
-}
+        let api_url = if api_version.is_empty() {
+            format!("{}/", base_url)
+        } else {
+            format!("{}/{}/", base_url, api_version)
+        };
 
-pub fn graph(username: &str, graph_id: &str) -> String {
-    let graphs_url = graphs(username);
-    String::from(format!("{}/{}", graphs_url, graph_id))
-}
+        Self {
+            base_url: base_url.to_owned(),
+            api_url,
+        }
+    }
 
-pub fn graph_svg(username: &str, graph_id: &str, date: Option<&str>) -> String {
-    let graphs_url = graphs(username);
+    /// The profile page is served from the base url, outside of the api version.
+    pub fn profile(&self, username: &str) -> String {
+        format!("{}/@{}", self.base_url, username)
+    }
 
-    if let Some(v) = date {
-        // ToDo : query param move to HttpClient.
-        return String::from(format!("{}/{}?date={}", graphs_url, graph_id, v))    
+    pub fn users(&self) -> String {
+        format!("{}users", self.api_url)
     }
 
-    String::from(format!("{}/{}", graphs_url, graph_id))
-}
+    pub fn user(&self, username: &str) -> String {
+        format!("{}users/{}", self.api_url, username)
+    }
 
-pub fn pixel(username: &str, graph_id: &str, date: &str) -> String {
-    let graph_url = graph(username, graph_id);
-    String::from(format!("{}/{}", graph_url, date))
-}
+    pub fn graphs(&self, username: &str) -> String {
+        let user_url = self.user(username);
+        format!("{}/graphs", user_url)
+    }
 
-pub fn increment(username: &str, graph_id: &str) -> String {
-    let graph_url = graph(username, graph_id);
-    String::from(format!("{}/{}", graph_url, "increment"))
-}
+    pub fn graph(&self, username: &str, graph_id: &str) -> String {
+        let graphs_url = self.graphs(username);
+        format!("{}/{}", graphs_url, graph_id)
+    }
+
+    pub fn graph_def(&self, username: &str, graph_id: &str) -> String {
+        let graph_url = self.graph(username, graph_id);
+        format!("{}/graph-def", graph_url)
+    }
+
+    pub fn graph_stats(&self, username: &str, graph_id: &str) -> String {
+        let graph_url = self.graph(username, graph_id);
+        format!("{}/stats", graph_url)
+    }
+
+    pub fn graph_svg(&self, username: &str, graph_id: &str) -> String {
+        let graphs_url = self.graphs(username);
+        format!("{}/{}", graphs_url, graph_id)
+    }
+
+    pub fn graph_html(&self, username: &str, graph_id: &str) -> String {
+        let graphs_url = self.graphs(username);
+        format!("{}/{}.html", graphs_url, graph_id)
+    }
+
+    pub fn purge_cache(&self, username: &str, graph_id: &str) -> String {
+        let graph_url = self.graph(username, graph_id);
+        format!("{}/{}", graph_url, "purge-cache")
+    }
+
+    pub fn pixel(&self, username: &str, graph_id: &str, date: &str) -> String {
+        let graph_url = self.graph(username, graph_id);
+        format!("{}/{}", graph_url, date)
+    }
+
+    pub fn pixels(&self, username: &str, graph_id: &str) -> String {
+        let graph_url = self.graph(username, graph_id);
+        format!("{}/pixels", graph_url)
+    }
+
+    pub fn latest(&self, username: &str, graph_id: &str) -> String {
+        let graph_url = self.graph(username, graph_id);
+        format!("{}/{}", graph_url, "latest")
+    }
+
+    pub fn today(&self, username: &str, graph_id: &str) -> String {
+        let graph_url = self.graph(username, graph_id);
+        format!("{}/{}", graph_url, "today")
+    }
+
+    pub fn add(&self, username: &str, graph_id: &str) -> String {
+        let graph_url = self.graph(username, graph_id);
+        format!("{}/{}", graph_url, "add")
+    }
+
+    pub fn subtract(&self, username: &str, graph_id: &str) -> String {
+        let graph_url = self.graph(username, graph_id);
+        format!("{}/{}", graph_url, "subtract")
+    }
+
+    pub fn stopwatch(&self, username: &str, graph_id: &str) -> String {
+        let graph_url = self.graph(username, graph_id);
+        format!("{}/{}", graph_url, "stopwatch")
+    }
+
+    pub fn increment(&self, username: &str, graph_id: &str) -> String {
+        let graph_url = self.graph(username, graph_id);
+        format!("{}/{}", graph_url, "increment")
+    }
+
+    pub fn decrement(&self, username: &str, graph_id: &str) -> String {
+        let graph_url = self.graph(username, graph_id);
+        format!("{}/{}", graph_url, "decrement")
+    }
+
+    pub fn channels(&self, username: &str) -> String {
+        let user_url = self.user(username);
+        format!("{}/channels", user_url)
+    }
+
+    pub fn channel(&self, username: &str, channel_id: &str) -> String {
+        let channels_url = self.channels(username);
+        format!("{}/{}", channels_url, channel_id)
+    }
 
-pub fn decrement(username: &str, graph_id: &str) -> String {
-    let graph_url = graph(username, graph_id);
-    String::from(format!("{}/{}", graph_url, "decrement"))
+    pub fn notifications(&self, username: &str, graph_id: &str) -> String {
+        let graph_url = self.graph(username, graph_id);
+        format!("{}/notifications", graph_url)
+    }
+
+    pub fn notification(&self, username: &str, graph_id: &str, notification_id: &str) -> String {
+        let notifications_url = self.notifications(username, graph_id);
+        format!("{}/{}", notifications_url, notification_id)
+    }
+
+    pub fn webhooks(&self, username: &str) -> String {
+        let user_url = self.user(username);
+        format!("{}/webhooks", user_url)
+    }
+
+    pub fn webhook(&self, username: &str, webhook_hash: &str) -> String {
+        let webhooks_url = self.webhooks(username);
+        format!("{}/{}", webhooks_url, webhook_hash)
+    }
 }
 
+impl Default for Endpoint {
+    fn default() -> Self {
+        Endpoint::new(DEFAULT_BASE_URL, DEFAULT_API_VERSION)
+    }
+}
 
 #[cfg(test)]
 mod endpoint_test {
+    mod base_url {
+        use super::super::*;
+
+        #[test]
+        fn default_base_url_test() {
+            assert_eq!(Endpoint::default().users(), "https://pixe.la/v1/users");
+        }
+
+        #[test]
+        fn custom_base_url_test() {
+            let endpoint = Endpoint::new("http://localhost:8080", "v1");
+            assert_eq!(endpoint.users(), "http://localhost:8080/v1/users");
+        }
+
+        #[test]
+        fn trim_slash_test() {
+            let endpoint = Endpoint::new("http://localhost:8080/", "/v2/");
+            assert_eq!(endpoint.users(), "http://localhost:8080/v2/users");
+        }
+
+        #[test]
+        fn empty_api_version_test() {
+            let endpoint = Endpoint::new("http://localhost:8080", "");
+            assert_eq!(endpoint.users(), "http://localhost:8080/users");
+        }
+    }
+
     mod user {
         use super::super::*;
 
         #[test]
         fn users_test() {
-            assert_eq!(users(), "https://pixe.la/v1/users");
+            assert_eq!(Endpoint::default().users(), "https://pixe.la/v1/users");
         }
 
         #[test]
         fn user_test() {
             let username = "testuser";
-            let expect_url = String::from(format!("https://pixe.la/v1/users/{}", username));
-            assert_eq!(user(username), expect_url);
+            let expect_url = format!("https://pixe.la/v1/users/{}", username);
+            assert_eq!(Endpoint::default().user(username), expect_url);
+        }
+    }
+
+    mod profile {
+        use super::super::*;
+
+        #[test]
+        fn profile_test() {
+            let username = "testuser";
+            let expect_url = format!("https://pixe.la/@{}", username);
+            assert_eq!(Endpoint::default().profile(username), expect_url);
+        }
+
+        #[test]
+        fn profile_ignores_api_version_test() {
+            let endpoint = Endpoint::new("http://localhost:8080/", "v2");
+            assert_eq!(endpoint.profile("testuser"), "http://localhost:8080/@testuser");
         }
     }
 
@@ -69,31 +223,56 @@ mod endpoint_test {
         #[test]
         fn graphs_test() {
             let username = "testuser";
-            let expect_url = String::from(format!("https://pixe.la/v1/users/{}/graphs", username));
-            assert_eq!(graphs(username), expect_url);
+            let expect_url = format!("https://pixe.la/v1/users/{}/graphs", username);
+            assert_eq!(Endpoint::default().graphs(username), expect_url);
         }
 
         #[test]
         fn graph_test() {
             let username = "testuser";
             let graph_id = "testid";
-            let expect_url = String::from(format!("https://pixe.la/v1/users/{}/graphs/{}", username, graph_id));
-            assert_eq!(graph(username, graph_id), expect_url);
+            let expect_url = format!("https://pixe.la/v1/users/{}/graphs/{}", username, graph_id);
+            assert_eq!(Endpoint::default().graph(username, graph_id), expect_url);
+        }
+
+        #[test]
+        fn graph_def_test() {
+            let username = "testuser";
+            let graph_id = "testid";
+            let expect_url = format!("https://pixe.la/v1/users/{}/graphs/{}/graph-def", username, graph_id);
+            assert_eq!(Endpoint::default().graph_def(username, graph_id), expect_url);
+        }
+
+        #[test]
+        fn graph_stats_test() {
+            let username = "testuser";
+            let graph_id = "testid";
+            let expect_url = format!("https://pixe.la/v1/users/{}/graphs/{}/stats", username, graph_id);
+            assert_eq!(Endpoint::default().graph_stats(username, graph_id), expect_url);
         }
 
         #[test]
         fn graph_svg_test() {
             let username = "testuser";
             let graph_id = "testid";
+            let expect_url = format!("https://pixe.la/v1/users/{}/graphs/{}", username, graph_id);
+            assert_eq!(Endpoint::default().graph_svg(username, graph_id), expect_url);
+        }
 
-            {
-                let expect_url = String::from(format!("https://pixe.la/v1/users/{}/graphs/{}", username, graph_id));
-                assert_eq!(graph_svg(username, graph_id, None), expect_url);
-            }
-            {
-                let expect_url = String::from(format!("https://pixe.la/v1/users/{}/graphs/{}?date={}", username, graph_id, "20181018"));
-                assert_eq!(graph_svg(username, graph_id, Some("20181018")), expect_url);
-            }
+        #[test]
+        fn purge_cache_test() {
+            let username = "testuser";
+            let graph_id = "testid";
+            let expect_url = format!("https://pixe.la/v1/users/{}/graphs/{}/purge-cache", username, graph_id);
+            assert_eq!(Endpoint::default().purge_cache(username, graph_id), expect_url);
+        }
+
+        #[test]
+        fn graph_html_test() {
+            let username = "testuser";
+            let graph_id = "testid";
+            let expect_url = format!("https://pixe.la/v1/users/{}/graphs/{}.html", username, graph_id);
+            assert_eq!(Endpoint::default().graph_html(username, graph_id), expect_url);
         }
     }
 
@@ -105,24 +284,131 @@ mod endpoint_test {
             let username = "testuser";
             let graph_id = "testid";
             let date = "20180101";
-            let expect_url = String::from(format!("https://pixe.la/v1/users/{}/graphs/{}/{}", username, graph_id, date));
-            assert_eq!(pixel(username, graph_id, date), expect_url);
+            let expect_url = format!("https://pixe.la/v1/users/{}/graphs/{}/{}", username, graph_id, date);
+            assert_eq!(Endpoint::default().pixel(username, graph_id, date), expect_url);
+        }
+
+        #[test]
+        fn pixels_test() {
+            let username = "testuser";
+            let graph_id = "testid";
+            let expect_url = format!("https://pixe.la/v1/users/{}/graphs/{}/pixels", username, graph_id);
+            assert_eq!(Endpoint::default().pixels(username, graph_id), expect_url);
+        }
+
+        #[test]
+        fn latest_test() {
+            let username = "testuser";
+            let graph_id = "testid";
+            let expect_url = format!("https://pixe.la/v1/users/{}/graphs/{}/latest", username, graph_id);
+            assert_eq!(Endpoint::default().latest(username, graph_id), expect_url);
+        }
+
+        #[test]
+        fn today_test() {
+            let username = "testuser";
+            let graph_id = "testid";
+            let expect_url = format!("https://pixe.la/v1/users/{}/graphs/{}/today", username, graph_id);
+            assert_eq!(Endpoint::default().today(username, graph_id), expect_url);
+        }
+
+        #[test]
+        fn add_test() {
+            let username = "testuser";
+            let graph_id = "testid";
+            let expect_url = format!("https://pixe.la/v1/users/{}/graphs/{}/add", username, graph_id);
+            assert_eq!(Endpoint::default().add(username, graph_id), expect_url);
+        }
+
+        #[test]
+        fn subtract_test() {
+            let username = "testuser";
+            let graph_id = "testid";
+            let expect_url = format!("https://pixe.la/v1/users/{}/graphs/{}/subtract", username, graph_id);
+            assert_eq!(Endpoint::default().subtract(username, graph_id), expect_url);
+        }
+
+        #[test]
+        fn stopwatch_test() {
+            let username = "testuser";
+            let graph_id = "testid";
+            let expect_url = format!("https://pixe.la/v1/users/{}/graphs/{}/stopwatch", username, graph_id);
+            assert_eq!(Endpoint::default().stopwatch(username, graph_id), expect_url);
         }
 
         #[test]
         fn increment_test() {
             let username = "testuser";
             let graph_id = "testid";
-            let expect_url = String::from(format!("https://pixe.la/v1/users/{}/graphs/{}/increment", username, graph_id));
-            assert_eq!(increment(username, graph_id), expect_url);
+            let expect_url = format!("https://pixe.la/v1/users/{}/graphs/{}/increment", username, graph_id);
+            assert_eq!(Endpoint::default().increment(username, graph_id), expect_url);
         }
 
         #[test]
         fn decrement_test() {
             let username = "testuser";
             let graph_id = "testid";
-            let expect_url = String::from(format!("https://pixe.la/v1/users/{}/graphs/{}/decrement", username, graph_id));
-            assert_eq!(decrement(username, graph_id), expect_url);
+            let expect_url = format!("https://pixe.la/v1/users/{}/graphs/{}/decrement", username, graph_id);
+            assert_eq!(Endpoint::default().decrement(username, graph_id), expect_url);
+        }
+    }
+
+    mod channel {
+        use super::super::*;
+
+        #[test]
+        fn channels_test() {
+            let username = "testuser";
+            let expect_url = format!("https://pixe.la/v1/users/{}/channels", username);
+            assert_eq!(Endpoint::default().channels(username), expect_url);
+        }
+
+        #[test]
+        fn channel_test() {
+            let username = "testuser";
+            let channel_id = "testchannel";
+            let expect_url = format!("https://pixe.la/v1/users/{}/channels/{}", username, channel_id);
+            assert_eq!(Endpoint::default().channel(username, channel_id), expect_url);
+        }
+    }
+
+    mod notification {
+        use super::super::*;
+
+        #[test]
+        fn notifications_test() {
+            let username = "testuser";
+            let graph_id = "testid";
+            let expect_url = format!("https://pixe.la/v1/users/{}/graphs/{}/notifications", username, graph_id);
+            assert_eq!(Endpoint::default().notifications(username, graph_id), expect_url);
+        }
+
+        #[test]
+        fn notification_test() {
+            let username = "testuser";
+            let graph_id = "testid";
+            let notification_id = "testrule";
+            let expect_url = format!("https://pixe.la/v1/users/{}/graphs/{}/notifications/{}", username, graph_id, notification_id);
+            assert_eq!(Endpoint::default().notification(username, graph_id, notification_id), expect_url);
+        }
+    }
+
+    mod webhook {
+        use super::super::*;
+
+        #[test]
+        fn webhooks_test() {
+            let username = "testuser";
+            let expect_url = format!("https://pixe.la/v1/users/{}/webhooks", username);
+            assert_eq!(Endpoint::default().webhooks(username), expect_url);
+        }
+
+        #[test]
+        fn webhook_test() {
+            let username = "testuser";
+            let webhook_hash = "0a1b2c";
+            let expect_url = format!("https://pixe.la/v1/users/{}/webhooks/{}", username, webhook_hash);
+            assert_eq!(Endpoint::default().webhook(username, webhook_hash), expect_url);
         }
     }
-}
\ No newline at end of file
+}
@@ -1,15 +1,127 @@
+// failure's `Fail` derive writes its impls inside a named const, which newer rustc reports as
+// `non_local_definitions`. The lint is allowed for the whole module because an attribute
+// on the enum does not reach the generated impls.
+#![allow(non_local_definitions)]
+
 use reqwest;
 use serde_json;
+use tokio_timer;
+use std::io;
 
 /// The Errors that may occur when processing a Request.
 #[derive(Debug, Fail)]
 pub enum PixelaClientError {
     #[fail(display = "request failed: {}", _0)]
     HttpClientError(reqwest::Error),
+    #[fail(display = "invalid url: {}", _0)]
+    InvalidUrl(reqwest::UrlError),
+    #[fail(display = "retry timer failed: {}", _0)]
+    TimerError(tokio_timer::Error),
     #[fail(display = "failed parse json: {}", _0)]
     ResponseParseFailed(serde_json::Error),
-    #[fail(display = "request is not success: {}", _0)]
-    RequestNotSuccess(String),
+    #[fail(display = "request is not success ({}): {}", status, message)]
+    RequestNotSuccess {
+        /// HTTP status code of the response.
+        status: u16,
+        /// The `message` returned by Pixela.
+        message: String,
+        /// The classified kind of the failure.
+        kind: ErrorKind,
+    },
+    #[fail(display = "invalid {}: it must match {}", name, rule)]
+    InvalidParameter {
+        /// Name of the parameter.
+        name: &'static str,
+        /// The validation rule the parameter violated.
+        rule: &'static str,
+    },
+    #[fail(display = "invalid value of header {}", _0)]
+    InvalidHeaderValue(&'static str),
+    #[fail(display = "io failed: {}", _0)]
+    IoError(io::Error),
+}
+
+/// The classified kind of a request which is not success in Pixela.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The user does not exist or the token is wrong.
+    InvalidToken,
+    /// The user already exists.
+    UserAlreadyExists,
+    /// The graph does not exist.
+    GraphNotFound,
+    /// The graph already exists.
+    GraphAlreadyExists,
+    /// The pixel does not exist.
+    PixelNotFound,
+    /// The request was rejected at random because the user is not a Pixela supporter. It can be retried.
+    Rejected,
+    /// The request parameters are invalid.
+    BadRequest,
+    /// Pixela failed to process the request.
+    ServerError,
+    /// Any other failure.
+    Other,
+}
+
+impl ErrorKind {
+    /// Classifies a failure from the HTTP status and the `message` returned by Pixela.
+    pub fn classify(status: u16, message: &str) -> Self {
+        let message = message.to_lowercase();
+
+        if message.contains("please retry this request") {
+            ErrorKind::Rejected
+        } else if message.contains("token is wrong") {
+            ErrorKind::InvalidToken
+        } else if message.contains("user") && message.contains("already exist") {
+            ErrorKind::UserAlreadyExists
+        } else if message.contains("graph") && message.contains("already exist") {
+            ErrorKind::GraphAlreadyExists
+        } else if message.contains("graph not found") || (message.contains("graph") && message.contains("not exist")) {
+            ErrorKind::GraphNotFound
+        } else if message.contains("pixel not found") {
+            ErrorKind::PixelNotFound
+        } else if status >= 500 {
+            ErrorKind::ServerError
+        } else if status == 400 {
+            ErrorKind::BadRequest
+        } else {
+            ErrorKind::Other
+        }
+    }
+}
+
+impl PixelaClientError {
+    pub(crate) fn request_not_success(status: u16, message: String, is_rejected: bool) -> Self {
+        let kind = if is_rejected {
+            ErrorKind::Rejected
+        } else {
+            ErrorKind::classify(status, &message)
+        };
+
+        PixelaClientError::RequestNotSuccess { status, message, kind }
+    }
+
+    /// Returns the classified kind when the request is not success in Pixela.
+    pub fn kind(&self) -> Option<ErrorKind> {
+        match self {
+            PixelaClientError::RequestNotSuccess { kind, .. } => Some(*kind),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` when Pixela could not be reached or failed to process the request.
+    pub fn is_unavailable(&self) -> bool {
+        matches!(self, PixelaClientError::HttpClientError(_) | PixelaClientError::RequestNotSuccess { kind: ErrorKind::ServerError, .. })
+    }
+
+    /// Returns the HTTP status code when the request is not success in Pixela.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            PixelaClientError::RequestNotSuccess { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
 }
 
 impl From<reqwest::Error> for PixelaClientError {
@@ -18,8 +130,68 @@ impl From<reqwest::Error> for PixelaClientError {
     }
 }
 
+impl From<reqwest::UrlError> for PixelaClientError {
+    fn from(e: reqwest::UrlError) -> Self {
+        PixelaClientError::InvalidUrl(e)
+    }
+}
+
+impl From<tokio_timer::Error> for PixelaClientError {
+    fn from(e: tokio_timer::Error) -> Self {
+        PixelaClientError::TimerError(e)
+    }
+}
+
+impl From<io::Error> for PixelaClientError {
+    fn from(e: io::Error) -> Self {
+        PixelaClientError::IoError(e)
+    }
+}
+
 impl From<serde_json::Error> for PixelaClientError {
     fn from(e: serde_json::Error) -> Self {
         PixelaClientError::ResponseParseFailed(e)
     }
 }
+
+#[cfg(test)]
+mod error_test {
+    use super::*;
+
+    #[test]
+    fn classify_test() {
+        assert_eq!(ErrorKind::classify(400, "User `testuser` does not exist or the token is wrong."), ErrorKind::InvalidToken);
+        assert_eq!(ErrorKind::classify(409, "This user already exist."), ErrorKind::UserAlreadyExists);
+        assert_eq!(ErrorKind::classify(409, "This graph already exist."), ErrorKind::GraphAlreadyExists);
+        assert_eq!(ErrorKind::classify(404, "Specified graph not found."), ErrorKind::GraphNotFound);
+        assert_eq!(ErrorKind::classify(404, "Specified pixel not found."), ErrorKind::PixelNotFound);
+        assert_eq!(ErrorKind::classify(503, "Please retry this request. Your request for some APIs will be rejected 25% of the time because you are not a Pixela supporter."), ErrorKind::Rejected);
+        assert_eq!(ErrorKind::classify(400, "Specified quantity is invalid."), ErrorKind::BadRequest);
+        assert_eq!(ErrorKind::classify(500, "Internal server error."), ErrorKind::ServerError);
+        assert_eq!(ErrorKind::classify(403, "Forbidden."), ErrorKind::Other);
+    }
+
+    #[test]
+    fn request_not_success_test() {
+        let e = PixelaClientError::request_not_success(404, "Specified pixel not found.".to_owned(), false);
+        assert_eq!(e.kind(), Some(ErrorKind::PixelNotFound));
+        assert_eq!(e.status(), Some(404));
+
+        let e = PixelaClientError::request_not_success(503, "Rejected.".to_owned(), true);
+        assert_eq!(e.kind(), Some(ErrorKind::Rejected));
+        assert!(!e.is_unavailable());
+
+        let e = PixelaClientError::request_not_success(502, "Bad gateway.".to_owned(), false);
+        assert!(e.is_unavailable());
+    }
+
+    #[test]
+    fn invalid_url_test() {
+        let e: PixelaClientError = reqwest::Url::parse("localhost").unwrap_err().into();
+        match e {
+            PixelaClientError::InvalidUrl(reqwest::UrlError::RelativeUrlWithoutBase) => {},
+            _ => panic!("not an invalid url error."),
+        }
+        assert!(!e.is_unavailable());
+    }
+}
@@ -1,19 +1,24 @@
 use super::Authentication;
-use super::endpoint;
+use super::endpoint::Endpoint;
 use super::response;
-use super::response::{ApiRequestResult};
-use super::http_client::{HttpClient, RequestContext, MethodType};
+use super::api_call::ApiCall;
+use super::http_client::{RequestContext, HttpResponse, MethodType, HeaderType};
 use super::error::PixelaClientError;
+use super::validation;
+use super::pixel::received_quantity;
+use super::types::{GraphId, PixelDate, Quantity};
 
-use failure::Error;
+use serde::{Deserialize, Deserializer};
+use serde::de;
 use serde_json;
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
 
 /// Graph definition in Pixela.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Graph {
     /// It is an ID for identifying the pixelation graph.
-    /// Validation rule: ^[a-z][a-z0-9-]{1,16}
-    pub id: String,
+    pub id: GraphId,
     /// It is the name of the pixelation graph.
     pub name: String,
     /// It is a unit of the quantity recorded in the pixelation graph. Ex. commit, kilogram, calory.
@@ -23,10 +28,108 @@ pub struct Graph {
     pub graph_type: GraphType,
     /// Defines the display color of the pixel in the pixelation graph.
     pub color: GraphColor,
+    /// The timezone for handling this graph, such as `Asia/Tokyo`. Pixela uses UTC when it is not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// URLs to which Pixela sends a purge request to the cache when the graph is updated. Up to 5 https URLs.
+    #[serde(rename = "purgeCacheURLs", default, skip_serializing_if = "Option::is_none")]
+    pub purge_cache_urls: Option<Vec<String>>,
+    /// Makes the pixel of the day increment or decrement automatically when it is not recorded.
+    #[serde(rename = "selfSufficient", default, skip_serializing_if = "Option::is_none")]
+    pub self_sufficient: Option<SelfSufficient>,
+    /// Hides the graph from the graph list and the user profile page.
+    #[serde(rename = "isSecret", default, skip_serializing_if = "Option::is_none")]
+    pub is_secret: Option<bool>,
+    /// Publishes the optional data of the pixels on the graph.
+    #[serde(rename = "publishOptionalData", default, skip_serializing_if = "Option::is_none")]
+    pub publish_optional_data: Option<bool>,
+}
+
+impl Graph {
+    /// Constructs a new `Graph` with the required fields, leaving the optional fields unset.
+    pub fn new(id: &GraphId, name: &str, unit: &str, graph_type: GraphType, color: GraphColor) -> Self {
+        Self {
+            id: id.to_owned(),
+            name: name.to_owned(),
+            unit: unit.to_owned(),
+            graph_type,
+            color,
+            timezone: None,
+            purge_cache_urls: None,
+            self_sufficient: None,
+            is_secret: None,
+            publish_optional_data: None,
+        }
+    }
+
+    /// Creates a `GraphBuilder` to configure the optional fields of a new `Graph`.
+    pub fn builder(id: &GraphId, name: &str, unit: &str, graph_type: GraphType, color: GraphColor) -> GraphBuilder {
+        GraphBuilder::new(id, name, unit, graph_type, color)
+    }
+}
+
+/// A `GraphBuilder` can be used to create a `Graph` for `create_graph`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let graph = Graph::builder(&graph_id, "graphname", "cal", GraphType::Int, GraphColor::Shibafu)
+///     .timezone("Asia/Tokyo")
+///     .is_secret(true)
+///     .build();
+/// client.create_graph(&graph)?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct GraphBuilder {
+    graph: Graph,
+}
+
+impl GraphBuilder {
+    /// Constructs a new `GraphBuilder` with the required fields of a graph.
+    pub fn new(id: &GraphId, name: &str, unit: &str, graph_type: GraphType, color: GraphColor) -> Self {
+        Self {
+            graph: Graph::new(id, name, unit, graph_type, color),
+        }
+    }
+
+    /// Sets the timezone for handling the graph, such as `Asia/Tokyo`.
+    pub fn timezone(mut self, timezone: &str) -> Self {
+        self.graph.timezone = Some(timezone.to_owned());
+        self
+    }
+
+    /// Sets the URLs to which Pixela sends a purge request to the cache when the graph is updated. Up to 5 https URLs.
+    pub fn purge_cache_urls(mut self, urls: &[&str]) -> Self {
+        self.graph.purge_cache_urls = Some(urls.iter().map(|v| v.to_string()).collect());
+        self
+    }
+
+    /// Sets the behavior on days without a recorded pixel.
+    pub fn self_sufficient(mut self, self_sufficient: SelfSufficient) -> Self {
+        self.graph.self_sufficient = Some(self_sufficient);
+        self
+    }
+
+    /// Sets whether the graph is hidden from the graph list and the user profile page.
+    pub fn is_secret(mut self, is_secret: bool) -> Self {
+        self.graph.is_secret = Some(is_secret);
+        self
+    }
+
+    /// Sets whether the optional data of the pixels is published.
+    pub fn publish_optional_data(mut self, publish_optional_data: bool) -> Self {
+        self.graph.publish_optional_data = Some(publish_optional_data);
+        self
+    }
+
+    /// Returns the configured `Graph`.
+    pub fn build(self) -> Graph {
+        self.graph
+    }
 }
 
 /// It is the type of quantity to be handled in the graph.
-#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum GraphType {
     #[serde(rename = "int")]
     Int,
@@ -35,7 +138,7 @@ pub enum GraphType {
 }
 
 /// Defines the display color of the pixel in the pixelation graph.
-#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum GraphColor {
     #[serde(rename = "shibafu")]
     Shibafu,
@@ -51,11 +154,116 @@ pub enum GraphColor {
     Kuro,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct UpdateGraphParam {
-    pub name: String,
-    pub unit: String,
-    pub color: GraphColor,
+/// The behavior of a graph on days without a recorded pixel.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelfSufficient {
+    #[serde(rename = "increment")]
+    Increment,
+    #[serde(rename = "decrement")]
+    Decrement,
+    #[serde(rename = "none")]
+    None,
+}
+
+/// Changes to a graph definition for `update_graph`. Only the fields which are set are sent to Pixela.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let update = GraphUpdate::new()
+///     .unit("kcal")
+///     .color(GraphColor::Ichou);
+/// client.update_graph(&graph_id, &update)?;
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct GraphUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    color: Option<GraphColor>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timezone: Option<String>,
+    #[serde(rename = "purgeCacheURLs", skip_serializing_if = "Option::is_none")]
+    purge_cache_urls: Option<Vec<String>>,
+    #[serde(rename = "selfSufficient", skip_serializing_if = "Option::is_none")]
+    self_sufficient: Option<SelfSufficient>,
+    #[serde(rename = "isSecret", skip_serializing_if = "Option::is_none")]
+    is_secret: Option<bool>,
+    #[serde(rename = "publishOptionalData", skip_serializing_if = "Option::is_none")]
+    publish_optional_data: Option<bool>,
+}
+
+impl GraphUpdate {
+    /// Constructs a new `GraphUpdate` which changes nothing.
+    pub fn new() -> Self {
+        GraphUpdate::default()
+    }
+
+    /// Sets the name of the graph.
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_owned());
+        self
+    }
+
+    /// Sets the unit of the quantity recorded in the graph.
+    pub fn unit(mut self, unit: &str) -> Self {
+        self.unit = Some(unit.to_owned());
+        self
+    }
+
+    /// Sets the display color of the pixels.
+    pub fn color(mut self, color: GraphColor) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Sets the timezone for handling the graph, such as `Asia/Tokyo`.
+    pub fn timezone(mut self, timezone: &str) -> Self {
+        self.timezone = Some(timezone.to_owned());
+        self
+    }
+
+    /// Sets the URLs to which Pixela sends a purge request to the cache when the graph is updated. Up to 5 https URLs.
+    pub fn purge_cache_urls(mut self, urls: &[&str]) -> Self {
+        self.purge_cache_urls = Some(urls.iter().map(|v| v.to_string()).collect());
+        self
+    }
+
+    /// Sets the behavior on days without a recorded pixel.
+    pub fn self_sufficient(mut self, self_sufficient: SelfSufficient) -> Self {
+        self.self_sufficient = Some(self_sufficient);
+        self
+    }
+
+    /// Sets whether the graph is hidden from the graph list and the user profile page.
+    pub fn is_secret(mut self, is_secret: bool) -> Self {
+        self.is_secret = Some(is_secret);
+        self
+    }
+
+    /// Sets whether the optional data of the pixels is published.
+    pub fn publish_optional_data(mut self, publish_optional_data: bool) -> Self {
+        self.publish_optional_data = Some(publish_optional_data);
+        self
+    }
+}
+
+/// Sends every updatable field of the graph.
+impl<'a> From<&'a Graph> for GraphUpdate {
+    fn from(graph: &'a Graph) -> Self {
+        Self {
+            name: Some(graph.name.to_owned()),
+            unit: Some(graph.unit.to_owned()),
+            color: Some(graph.color),
+            timezone: graph.timezone.to_owned(),
+            purge_cache_urls: graph.purge_cache_urls.to_owned(),
+            self_sufficient: graph.self_sufficient,
+            is_secret: graph.is_secret,
+            publish_optional_data: graph.publish_optional_data,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -63,84 +271,399 @@ pub struct GraphDefinitions {
     pub graphs: Vec<Graph>,
 }
 
-pub(crate) fn create<T>(auth: &Authentication, param: &Graph) -> Result<(), Error> where T: HttpClient {
-    let uri = endpoint::graphs(&auth.username);
+/// Statistics of a graph in Pixela.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GraphStats {
+    /// The number of recorded pixels.
+    #[serde(rename = "totalPixelsCount")]
+    pub total_pixels_count: u64,
+    /// The largest quantity.
+    #[serde(rename = "maxQuantity")]
+    pub max_quantity: Quantity,
+    /// The date of the largest quantity. It is `None` when no pixel is recorded.
+    #[serde(rename = "maxDate", default, deserialize_with = "deserialize_stats_date")]
+    pub max_date: Option<PixelDate>,
+    /// The smallest quantity.
+    #[serde(rename = "minQuantity")]
+    pub min_quantity: Quantity,
+    /// The date of the smallest quantity. It is `None` when no pixel is recorded.
+    #[serde(rename = "minDate", default, deserialize_with = "deserialize_stats_date")]
+    pub min_date: Option<PixelDate>,
+    /// The sum of all quantities.
+    #[serde(rename = "totalQuantity")]
+    pub total_quantity: Quantity,
+    /// The average of all quantities.
+    #[serde(rename = "avgQuantity")]
+    pub avg_quantity: f64,
+    /// The quantity of today in the timezone of the graph.
+    #[serde(rename = "todaysQuantity")]
+    pub todays_quantity: Quantity,
+    /// The quantity of yesterday in the timezone of the graph.
+    #[serde(rename = "yesterdayQuantity", default)]
+    pub yesterday_quantity: Option<Quantity>,
+}
+
+/// Pixela returns the dates of the statistics in yyyy-MM-dd format, and an empty string when there is no pixel.
+fn deserialize_stats_date<'de, D>(deserializer: D) -> Result<Option<PixelDate>, D::Error> where D: Deserializer<'de> {
+    let value: Option<String> = Option::deserialize(deserializer)?;
+    match value {
+        Some(ref v) if !v.is_empty() => PixelDate::new(&v.replace('-', "")).map(Some).map_err(de::Error::custom),
+        _ => Ok(None),
+    }
+}
+
+/// Display modes of a graph SVG.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SvgMode {
+    /// Only the last 90 days.
+    Short,
+    /// A badge with the quantity of the latest pixel.
+    Badge,
+    /// A line chart.
+    Line,
+}
+
+impl SvgMode {
+    fn as_str(self) -> &'static str {
+        match self {
+            SvgMode::Short => "short",
+            SvgMode::Badge => "badge",
+            SvgMode::Line => "line",
+        }
+    }
+}
+
+/// Appearances of a graph SVG.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SvgAppearance {
+    Dark,
+}
+
+impl SvgAppearance {
+    fn as_str(self) -> &'static str {
+        match self {
+            SvgAppearance::Dark => "dark",
+        }
+    }
+}
+
+/// Options for `get_graph_svg`. Only the options which are set are sent to Pixela.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let options = SvgOptions::new()
+///     .mode(SvgMode::Short)
+///     .appearance(SvgAppearance::Dark)
+///     .greater_than(Quantity::Int(3));
+/// let svg = client.get_graph_svg(&graph_id, &options)?;
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SvgOptions {
+    date: Option<PixelDate>,
+    mode: Option<SvgMode>,
+    appearance: Option<SvgAppearance>,
+    less_than: Option<Quantity>,
+    greater_than: Option<Quantity>,
+}
+
+impl SvgOptions {
+    /// Constructs a new `SvgOptions` with the default display of Pixela.
+    pub fn new() -> Self {
+        SvgOptions::default()
+    }
+
+    /// Sets the date of the last day shown in the graph.
+    pub fn date(mut self, date: &PixelDate) -> Self {
+        self.date = Some(date.to_owned());
+        self
+    }
+
+    /// Sets the display mode.
+    pub fn mode(mut self, mode: SvgMode) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    /// Sets the appearance.
+    pub fn appearance(mut self, appearance: SvgAppearance) -> Self {
+        self.appearance = Some(appearance);
+        self
+    }
+
+    /// Shows only the pixels whose quantity is less than the value.
+    pub fn less_than(mut self, quantity: Quantity) -> Self {
+        self.less_than = Some(quantity);
+        self
+    }
+
+    /// Shows only the pixels whose quantity is greater than the value.
+    pub fn greater_than(mut self, quantity: Quantity) -> Self {
+        self.greater_than = Some(quantity);
+        self
+    }
+
+    pub(crate) fn query(&self) -> Vec<(&'static str, String)> {
+        let mut query = Vec::new();
+        if let Some(ref v) = self.date {
+            query.push(("date", v.as_str().to_owned()));
+        }
+        if let Some(v) = self.mode {
+            query.push(("mode", v.as_str().to_owned()));
+        }
+        if let Some(v) = self.appearance {
+            query.push(("appearance", v.as_str().to_owned()));
+        }
+        if let Some(v) = self.less_than {
+            query.push(("lessThan", v.to_string()));
+        }
+        if let Some(v) = self.greater_than {
+            query.push(("greaterThan", v.to_string()));
+        }
+        query
+    }
+}
+
+/// Display modes of the graph detail page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphHtmlMode {
+    /// The full detail page with the statistics.
+    Detail,
+    /// Only the graph.
+    Simple,
+    /// Only the last 90 days of the graph.
+    SimpleShort,
+}
+
+impl GraphHtmlMode {
+    fn as_query(self) -> Option<&'static str> {
+        match self {
+            GraphHtmlMode::Detail => None,
+            GraphHtmlMode::Simple => Some("simple"),
+            GraphHtmlMode::SimpleShort => Some("simple-short"),
+        }
+    }
+}
+
+/// Public URLs of a graph, to link to it or embed it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphUrls {
+    /// The SVG image of the graph.
+    pub svg: String,
+    /// The detail page of the graph.
+    pub html: String,
+}
+
+/// Types of the graphs known to a client, used to check quantities before sending them.
+///
+/// A graph missing from the definitions, or looked up while they could not be fetched, is remembered as `None`,
+/// so it is not fetched again until `reset`.
+#[derive(Debug, Default)]
+pub(crate) struct GraphTypes(Mutex<HashMap<GraphId, Option<GraphType>>>);
+
+impl GraphTypes {
+    pub fn get(&self, graph_id: &GraphId) -> Option<GraphType> {
+        self.lookup(graph_id).and_then(|v| v)
+    }
+
+    /// Returns `None` when the graph is not known yet, and `Some(None)` when it is known to be missing.
+    pub fn lookup(&self, graph_id: &GraphId) -> Option<Option<GraphType>> {
+        self.lock().get(graph_id).cloned()
+    }
+
+    pub fn insert(&self, graph: &Graph) {
+        self.lock().insert(graph.id.to_owned(), Some(graph.graph_type));
+    }
+
+    /// Remembers that the type of the graph is not known, unless it was learned meanwhile.
+    pub fn insert_missing(&self, graph_id: &GraphId) {
+        self.lock().entry(graph_id.to_owned()).or_insert(None);
+    }
+
+    pub fn remove(&self, graph_id: &GraphId) {
+        self.lock().remove(graph_id);
+    }
+
+    /// Replaces the known types with the graphs returned by Pixela.
+    pub fn reset(&self, graphs: &[Graph]) {
+        let mut types = self.lock();
+        types.clear();
+        types.extend(graphs.iter().map(|v| (v.id.to_owned(), Some(v.graph_type))));
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<GraphId, Option<GraphType>>> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+pub(crate) fn create(endpoint: &Endpoint, auth: &Authentication, param: &Graph) -> Result<ApiCall<()>, PixelaClientError> {
+    if let Some(ref v) = param.purge_cache_urls {
+        validation::validate_purge_cache_urls(v)?;
+    }
+
+    let uri = endpoint.graphs(auth.username.as_str());
     let body = serde_json::to_string(param)?;
     let context = RequestContext::new(
         &uri,
         MethodType::Post,
         Some(&body),
-        Some(&auth.token),
+        Some(auth.token.as_str()),
     );
 
-    let body = T::do_request(&context)?;
-    response::build_result(&body)
+    Ok(ApiCall::new(context, response::build_result).non_idempotent())
 }
 
-pub(crate) fn update<T>(auth: &Authentication, graph_id: &str, param: &UpdateGraphParam) -> Result<(), Error> where T: HttpClient {
-    let uri = endpoint::graph(&auth.username, graph_id);
+pub(crate) fn update(endpoint: &Endpoint, auth: &Authentication, graph_id: &GraphId, param: &GraphUpdate) -> Result<ApiCall<()>, PixelaClientError> {
+    if let Some(ref v) = param.purge_cache_urls {
+        validation::validate_purge_cache_urls(v)?;
+    }
+
+    let uri = endpoint.graph(auth.username.as_str(), graph_id.as_str());
     let body = serde_json::to_string(param)?;
     let context = RequestContext::new(
         &uri,
         MethodType::Put,
         Some(&body),
-        Some(&auth.token),
+        Some(auth.token.as_str()),
     );
 
-    let body = T::do_request(&context)?;
-    response::build_result(&body)
+    Ok(ApiCall::new(context, response::build_result))
 }
 
-pub(crate) fn delete<T>(auth: &Authentication, graph_id: &str) -> Result<(), Error> where T: HttpClient {
-    let uri = endpoint::graph(&auth.username, graph_id);
+/// Purging twice is harmless, so the request is retried like any other update.
+pub(crate) fn purge_cache(endpoint: &Endpoint, auth: &Authentication, graph_id: &GraphId) -> Result<ApiCall<()>, PixelaClientError> {
+    let uri = endpoint.purge_cache(auth.username.as_str(), graph_id.as_str());
+    let mut context = RequestContext::new(
+        &uri,
+        MethodType::Put,
+        None,
+        Some(auth.token.as_str()),
+    );
+
+    context.insert_header(HeaderType::ContentLength, "0");
+
+    Ok(ApiCall::new(context, response::build_result))
+}
+
+pub(crate) fn delete(endpoint: &Endpoint, auth: &Authentication, graph_id: &GraphId) -> Result<ApiCall<()>, PixelaClientError> {
+    let uri = endpoint.graph(auth.username.as_str(), graph_id.as_str());
     let context = RequestContext::new(
         &uri,
         MethodType::Delete,
         None,
-        Some(&auth.token),
+        Some(auth.token.as_str()),
     );
 
-    let body = T::do_request(&context)?;
-    response::build_result(&body)
+    Ok(ApiCall::new(context, response::build_result).non_idempotent())
 }
 
-pub(crate) fn get_all<T>(auth: &Authentication) -> Result<Vec<Graph>, Error> where T: HttpClient {
-    let uri = endpoint::graphs(&auth.username);
+pub(crate) fn get_all(endpoint: &Endpoint, auth: &Authentication) -> Result<ApiCall<Vec<Graph>>, PixelaClientError> {
+    let uri = endpoint.graphs(auth.username.as_str());
     let context = RequestContext::new(
         &uri,
         MethodType::Get,
         None,
-        Some(&auth.token),
+        Some(auth.token.as_str()),
     );
 
-    let body = T::do_request(&context)?;
+    Ok(ApiCall::new(context, parse_graphs))
+}
 
-    let res: Result<ApiRequestResult, _> = serde_json::from_str(&body);
-    if let Ok(v) = res {
-        return Err(PixelaClientError::RequestNotSuccess(v.message).into());
-    }
+pub(crate) fn get(endpoint: &Endpoint, auth: &Authentication, graph_id: &GraphId) -> Result<ApiCall<Graph>, PixelaClientError> {
+    let uri = endpoint.graph_def(auth.username.as_str(), graph_id.as_str());
+    let context = RequestContext::new(
+        &uri,
+        MethodType::Get,
+        None,
+        Some(auth.token.as_str()),
+    );
+
+    Ok(ApiCall::new(context, parse_graph))
+}
+
+fn parse_graph(response: &HttpResponse) -> Result<Graph, PixelaClientError> {
+    response::check_error(response)?;
+
+    Ok(serde_json::from_str(&response.body)?)
+}
 
-    let res: GraphDefinitions = serde_json::from_str(&body)?;
+fn parse_graphs(response: &HttpResponse) -> Result<Vec<Graph>, PixelaClientError> {
+    response::check_error(response)?;
+
+    let res: GraphDefinitions = serde_json::from_str(&response.body)?;
     Ok(res.graphs)
 }
 
-pub(crate) fn get_graph_svg<T>(auth: &Authentication, graph_id: &str, date: Option<&str>) -> Result<String, Error> where T: HttpClient {
-    let uri = endpoint::graph_svg(&auth.username, graph_id, date);
+pub(crate) fn get_stats(endpoint: &Endpoint, auth: &Authentication, graph_id: &GraphId, graph_type: Option<GraphType>) -> Result<ApiCall<GraphStats>, PixelaClientError> {
+    let uri = endpoint.graph_stats(auth.username.as_str(), graph_id.as_str());
     let context = RequestContext::new(
         &uri,
         MethodType::Get,
         None,
-        Some(&auth.token),
+        Some(auth.token.as_str()),
     );
 
-    let body = T::do_request(&context)?;
+    Ok(ApiCall::new(context, move |res| parse_graph_stats(res, graph_type)))
+}
+
+fn parse_graph_stats(response: &HttpResponse, graph_type: Option<GraphType>) -> Result<GraphStats, PixelaClientError> {
+    response::check_error(response)?;
+
+    let stats: GraphStats = serde_json::from_str(&response.body)?;
+    Ok(GraphStats {
+        max_quantity: received_quantity(stats.max_quantity, graph_type),
+        min_quantity: received_quantity(stats.min_quantity, graph_type),
+        total_quantity: received_quantity(stats.total_quantity, graph_type),
+        todays_quantity: received_quantity(stats.todays_quantity, graph_type),
+        yesterday_quantity: stats.yesterday_quantity.map(|v| received_quantity(v, graph_type)),
+        ..stats
+    })
+}
 
-    let res: Result<ApiRequestResult, _> = serde_json::from_str(&body);
-    if let Ok(v) = res {
-        return Err(PixelaClientError::RequestNotSuccess(v.message).into());
+pub(crate) fn get_graph_svg(endpoint: &Endpoint, auth: &Authentication, graph_id: &GraphId, options: &SvgOptions) -> Result<ApiCall<String>, PixelaClientError> {
+    let uri = endpoint.graph_svg(auth.username.as_str(), graph_id.as_str());
+    let mut context = RequestContext::new(
+        &uri,
+        MethodType::Get,
+        None,
+        Some(auth.token.as_str()),
+    );
+
+    for (key, value) in options.query() {
+        context.insert_query(key, &value);
+    }
+
+    Ok(ApiCall::new(context, parse_graph_document))
+}
+
+pub(crate) fn get_graph_html(endpoint: &Endpoint, auth: &Authentication, graph_id: &GraphId, mode: GraphHtmlMode) -> Result<ApiCall<String>, PixelaClientError> {
+    let uri = endpoint.graph_html(auth.username.as_str(), graph_id.as_str());
+    let mut context = RequestContext::new(
+        &uri,
+        MethodType::Get,
+        None,
+        Some(auth.token.as_str()),
+    );
+
+    if let Some(v) = mode.as_query() {
+        context.insert_query("mode", v);
     }
 
-    Ok(body)
+    Ok(ApiCall::new(context, parse_graph_document))
+}
+
+pub(crate) fn urls(endpoint: &Endpoint, auth: &Authentication, graph_id: &GraphId) -> GraphUrls {
+    GraphUrls {
+        svg: endpoint.graph_svg(auth.username.as_str(), graph_id.as_str()),
+        html: endpoint.graph_html(auth.username.as_str(), graph_id.as_str()),
+    }
+}
+
+/// The SVG and the HTML of a graph are returned as they are.
+fn parse_graph_document(response: &HttpResponse) -> Result<String, PixelaClientError> {
+    response::check_error(response)?;
+
+    Ok(response.body.to_owned())
 }
 
 
@@ -148,15 +671,24 @@ pub(crate) fn get_graph_svg<T>(auth: &Authentication, graph_id: &str, date: Opti
 mod graph_test {
     use super::*;
 
+    #[test]
+    fn svg_options_query_test() {
+        assert!(SvgOptions::new().query().is_empty());
+
+        let options = SvgOptions::new()
+            .date(&PixelDate::new("20181018").unwrap())
+            .mode(SvgMode::Line)
+            .greater_than(Quantity::Int(3));
+        assert_eq!(options.query(), vec![
+            ("date", "20181018".to_owned()),
+            ("mode", "line".to_owned()),
+            ("greaterThan", "3".to_owned()),
+        ]);
+    }
+
     #[test]
     fn graph_serialize_test() {
-        let param = Graph {
-            id: String::from("testid"),
-            name: String::from("testname"),
-            unit: String::from("testunit"),
-            graph_type: GraphType::Int,
-            color: GraphColor::Kuro,
-        };
+        let param = Graph::new(&GraphId::new("testid").unwrap(), "testname", "testunit", GraphType::Int, GraphColor::Kuro);
 
         let res = serde_json::to_string(&param);
         if let Ok(v) = res {
@@ -171,10 +703,71 @@ mod graph_test {
         let json = r#"{"graphs":[{"id":"testid","name":"testname","unit":"testunit","type":"int","color":"kuro"}]}"#;
         let res: GraphDefinitions = serde_json::from_str(json).unwrap();
         assert_eq!(res.graphs.len(), 1);
-        assert_eq!(&res.graphs[0].id, "testid");
+        assert_eq!(res.graphs[0].id.as_str(), "testid");
         assert_eq!(&res.graphs[0].name, "testname");
         assert_eq!(&res.graphs[0].unit, "testunit");
         assert_eq!(&res.graphs[0].graph_type, &GraphType::Int);
         assert_eq!(&res.graphs[0].color, &GraphColor::Kuro);
+        assert_eq!(res.graphs[0].timezone, None);
+    }
+
+    #[test]
+    fn graph_def_test() {
+        let json = r#"{"id":"testid","name":"testname","unit":"testunit","type":"float","color":"sora","timezone":"Asia/Tokyo","purgeCacheURLs":["https://camo.githubusercontent.com/xxx"],"selfSufficient":"increment","isSecret":true,"publishOptionalData":false}"#;
+        let graph = parse_graph(&HttpResponse::new(200, json)).unwrap();
+        assert_eq!(graph.timezone, Some("Asia/Tokyo".to_owned()));
+        assert_eq!(graph.purge_cache_urls, Some(vec!["https://camo.githubusercontent.com/xxx".to_owned()]));
+        assert_eq!(graph.self_sufficient, Some(SelfSufficient::Increment));
+        assert_eq!(graph.is_secret, Some(true));
+        assert_eq!(graph.publish_optional_data, Some(false));
+
+        assert_eq!(serde_json::to_string(&graph).unwrap(), json);
+    }
+
+    #[test]
+    fn graph_builder_test() {
+        let graph = Graph::builder(&GraphId::new("testid").unwrap(), "testname", "testunit", GraphType::Int, GraphColor::Kuro)
+            .timezone("Asia/Tokyo")
+            .self_sufficient(SelfSufficient::Decrement)
+            .is_secret(true)
+            .build();
+
+        assert_eq!(serde_json::to_string(&graph).unwrap(), r#"{"id":"testid","name":"testname","unit":"testunit","type":"int","color":"kuro","timezone":"Asia/Tokyo","selfSufficient":"decrement","isSecret":true}"#);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn graph_update_test() {
+        let update = GraphUpdate::new().unit("kcal").publish_optional_data(false);
+        assert_eq!(serde_json::to_string(&update).unwrap(), r#"{"unit":"kcal","publishOptionalData":false}"#);
+
+        let mut graph = Graph::new(&GraphId::new("testid").unwrap(), "testname", "testunit", GraphType::Int, GraphColor::Kuro);
+        graph.is_secret = Some(true);
+
+        let update = GraphUpdate::from(&graph);
+        assert_eq!(serde_json::to_string(&update).unwrap(), r#"{"name":"testname","unit":"testunit","color":"kuro","isSecret":true}"#);
+    }
+
+    #[test]
+    fn graph_stats_test() {
+        let json = r#"{"totalPixelsCount":4,"maxQuantity":5,"maxDate":"2020-04-26","minQuantity":1,"minDate":"2020-04-22","totalQuantity":11,"avgQuantity":2.75,"todaysQuantity":0,"yesterdayQuantity":3}"#;
+        let stats = parse_graph_stats(&HttpResponse::new(200, json), Some(GraphType::Int)).unwrap();
+        assert_eq!(stats.total_pixels_count, 4);
+        assert_eq!(stats.max_quantity, Quantity::Int(5));
+        assert_eq!(stats.max_date, Some(PixelDate::new("20200426").unwrap()));
+        assert_eq!(stats.min_date, Some(PixelDate::new("20200422").unwrap()));
+        assert_eq!(stats.total_quantity, Quantity::Int(11));
+        assert_eq!(stats.avg_quantity, 2.75);
+        assert_eq!(stats.yesterday_quantity, Some(Quantity::Int(3)));
+
+        let stats = parse_graph_stats(&HttpResponse::new(200, json), Some(GraphType::Float)).unwrap();
+        assert_eq!(stats.max_quantity, Quantity::Float(5.0));
+        assert_eq!(stats.total_quantity, Quantity::Float(11.0));
+        assert_eq!(stats.todays_quantity, Quantity::Float(0.0));
+        assert_eq!(stats.yesterday_quantity, Some(Quantity::Float(3.0)));
+
+        let json = r#"{"totalPixelsCount":0,"maxQuantity":0,"maxDate":"","minQuantity":0,"minDate":"","totalQuantity":0,"avgQuantity":0,"todaysQuantity":0}"#;
+        let stats = parse_graph_stats(&HttpResponse::new(200, json), None).unwrap();
+        assert_eq!(stats.max_date, None);
+        assert_eq!(stats.yesterday_quantity, None);
+    }
+}
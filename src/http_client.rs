@@ -1,8 +1,15 @@
-use reqwest;
-use reqwest::Client;
-use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::{Client, ClientBuilder, Proxy, Url};
+use reqwest::r#async::{Client as AsyncClient, ClientBuilder as AsyncClientBuilder};
+use reqwest::header::{HeaderMap, HeaderValue, USER_AGENT};
+use super::error::PixelaClientError;
+use futures::{future, Future};
 use std::collections::HashMap;
+use std::time::Duration;
 
+const DEFAULT_USER_AGENT: &str = concat!("pixelast/", env!("CARGO_PKG_VERSION"));
+
+/// HTTP method of a request to Pixela.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MethodType {
     Get,
     Post,
@@ -10,72 +17,361 @@ pub enum MethodType {
     Delete,
 }
 
-#[derive(Hash, PartialEq, Eq)]
+/// HTTP header of a request to Pixela.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
 pub enum HeaderType {
+    /// `X-USER-TOKEN` header.
     UserToken,
+    /// `CONTENT-LENGTH` header.
     ContentLength,
 }
 
-pub struct RequestContext<'a> {
-    pub uri: &'a str,
+impl HeaderType {
+    /// Returns the name of the header.
+    pub fn name(&self) -> &'static str {
+        match self {
+            HeaderType::UserToken => "X-USER-TOKEN",
+            HeaderType::ContentLength => "CONTENT-LENGTH",
+        }
+    }
+}
+
+/// A request to Pixela to be sent by a `HttpClient`.
+///
+/// Use `url` to get the url with the query parameters encoded.
+#[derive(Debug)]
+pub struct RequestContext {
+    /// Request url without the query string.
+    pub uri: String,
+    /// Query parameters in order.
+    pub query: Vec<(String, String)>,
+    /// Request method.
     pub method: MethodType,
-    pub body: Option<&'a str>,
-    pub headers: HashMap<HeaderType, &'a str>,
+    /// Request body in JSON format.
+    pub body: Option<String>,
+    /// Request headers.
+    pub headers: HashMap<HeaderType, String>,
 }
 
-impl<'a> RequestContext<'a> {
-    pub fn new(uri: &'a str, method: MethodType, body: Option<&'a str>, token: Option<&'a str>) -> Self {
+impl RequestContext {
+    /// Constructs a new `RequestContext`. The token is sent as `X-USER-TOKEN` header.
+    pub fn new(uri: &str, method: MethodType, body: Option<&str>, token: Option<&str>) -> Self {
         let mut headers = HashMap::new();
         if let Some(v) = token {
-            headers.insert(HeaderType::UserToken, v);
+            headers.insert(HeaderType::UserToken, v.to_owned());
         }
 
         Self {
-            uri,
+            uri: uri.to_owned(),
+            query: Vec::new(),
             method,
-            body,
+            body: body.map(|v| v.to_owned()),
             headers,
         }
     }
 
-    pub fn insert_header(&mut self, header_type: HeaderType, value : &'a str) {
-        self.headers.insert(header_type, value);
+    /// Inserts a header into the request.
+    pub fn insert_header(&mut self, header_type: HeaderType, value: &str) {
+        self.headers.insert(header_type, value.to_owned());
+    }
+
+    /// Appends a query parameter to the request.
+    pub fn insert_query(&mut self, key: &str, value: &str) {
+        self.query.push((key.to_owned(), value.to_owned()));
+    }
+
+    /// Returns the request url with the query parameters encoded.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when the url is invalid.
+    pub fn url(&self) -> Result<String, PixelaClientError> {
+        if self.query.is_empty() {
+            return Ok(self.uri.to_owned());
+        }
+
+        let url = Url::parse_with_params(&self.uri, &self.query)
+            .map_err(PixelaClientError::InvalidUrl)?;
+        Ok(url.into_string())
+    }
+
+    fn header_map(&self) -> Result<HeaderMap, PixelaClientError> {
+        let mut headers = HeaderMap::new();
+        for (key, val) in self.headers.iter() {
+            let val = HeaderValue::from_str(val).map_err(|_| PixelaClientError::InvalidHeaderValue(key.name()))?;
+            headers.insert(key.name(), val);
+        }
+
+        Ok(headers)
+    }
+}
+
+/// A response from Pixela returned by a `HttpClient`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpResponse {
+    /// HTTP status code.
+    pub status: u16,
+    /// Response body.
+    pub body: String,
+}
+
+impl HttpResponse {
+    /// Constructs a new `HttpResponse`.
+    pub fn new(status: u16, body: &str) -> Self {
+        Self {
+            status,
+            body: body.to_owned(),
+        }
     }
 }
 
+/// A transport used by `PixelaClient` to send requests to Pixela.
+///
+/// Implement this trait to inject your own transport, a recording client or a test double.
 pub trait HttpClient {
-    fn do_request(context: &RequestContext) -> Result<String, reqwest::Error>;
+    /// Sends the request and returns the response.
+    ///
+    /// A response which is not success in HTTP status must be returned as `Ok`; it is classified by the caller.
+    fn do_request(&self, context: &RequestContext) -> Result<HttpResponse, PixelaClientError>;
+}
+
+/// A `TinyHttpClientBuilder` can be used to create a `TinyHttpClient` or a `TinyAsyncHttpClient` with custom configuration.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let http_client = TinyHttpClient::builder()
+///     .timeout(Duration::from_secs(10))
+///     .user_agent("my-service/1.0")
+///     .proxy(Proxy::https("http://proxy.example.com:8080")?)
+///     .build()?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct TinyHttpClientBuilder {
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    user_agent: String,
+    proxy: Option<Proxy>,
+    max_idle_per_host: Option<usize>,
+}
+
+impl TinyHttpClientBuilder {
+    /// Constructs a new `TinyHttpClientBuilder`.
+    pub fn new() -> Self {
+        Self {
+            timeout: None,
+            connect_timeout: None,
+            user_agent: DEFAULT_USER_AGENT.to_owned(),
+            proxy: None,
+            max_idle_per_host: None,
+        }
+    }
+
+    /// Sets the timeout of a whole request. Default is 30 seconds for `TinyHttpClient` and no timeout for `TinyAsyncHttpClient`.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the timeout of the connect phase. Default is no timeout.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the `User-Agent` header sent with every request. Default is `pixelast/<version>`.
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = user_agent.to_owned();
+        self
+    }
+
+    /// Sends every request through the proxy.
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Sets the maximum idle connections kept alive per host in the connection pool.
+    pub fn max_idle_per_host(mut self, max: usize) -> Self {
+        self.max_idle_per_host = Some(max);
+        self
+    }
+
+    /// Returns a `TinyHttpClient` that uses this `TinyHttpClientBuilder` configuration.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when the user agent is not a valid header value or the TLS backend cannot be initialized.
+    pub fn build(self) -> Result<TinyHttpClient, PixelaClientError> {
+        let mut builder = ClientBuilder::new().default_headers(self.default_headers()?);
+
+        if let Some(v) = self.timeout {
+            builder = builder.timeout(v);
+        }
+        if let Some(v) = self.connect_timeout {
+            builder = builder.connect_timeout(v);
+        }
+        if let Some(v) = self.proxy {
+            builder = builder.proxy(v);
+        }
+        if let Some(v) = self.max_idle_per_host {
+            builder = builder.max_idle_per_host(v);
+        }
+
+        Ok(TinyHttpClient { client: builder.build()? })
+    }
+
+    /// Returns a `TinyAsyncHttpClient` that uses this `TinyHttpClientBuilder` configuration.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when the user agent is not a valid header value or the TLS backend cannot be initialized.
+    pub fn build_async(self) -> Result<TinyAsyncHttpClient, PixelaClientError> {
+        let mut builder = AsyncClientBuilder::new().default_headers(self.default_headers()?);
+
+        if let Some(v) = self.timeout {
+            builder = builder.timeout(v);
+        }
+        if let Some(v) = self.connect_timeout {
+            builder = builder.connect_timeout(v);
+        }
+        if let Some(v) = self.proxy {
+            builder = builder.proxy(v);
+        }
+        if let Some(v) = self.max_idle_per_host {
+            builder = builder.max_idle_per_host(v);
+        }
+
+        Ok(TinyAsyncHttpClient { client: builder.build()? })
+    }
+
+    fn default_headers(&self) -> Result<HeaderMap, PixelaClientError> {
+        let user_agent = HeaderValue::from_str(&self.user_agent)
+            .map_err(|_| PixelaClientError::InvalidHeaderValue("USER-AGENT"))?;
+
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, user_agent);
+        Ok(headers)
+    }
+}
+
+impl Default for TinyHttpClientBuilder {
+    fn default() -> Self {
+        TinyHttpClientBuilder::new()
+    }
+}
+
+/// The default `HttpClient` built on reqwest.
+///
+/// It holds a long-lived reqwest client, so connections are pooled and kept alive between requests.
+#[derive(Debug, Clone)]
+pub struct TinyHttpClient {
+    client: Client,
+}
+
+impl TinyHttpClient {
+    /// Constructs a new `TinyHttpClient` with the default configuration.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when the TLS backend cannot be initialized.
+    pub fn new() -> Result<Self, PixelaClientError> {
+        TinyHttpClientBuilder::new().build()
+    }
+
+    /// Creates a `TinyHttpClientBuilder` to configure a `TinyHttpClient`.
+    pub fn builder() -> TinyHttpClientBuilder {
+        TinyHttpClientBuilder::new()
+    }
 }
 
-pub struct TinyHttpClient;
 impl HttpClient for TinyHttpClient {
-    fn do_request(context: &RequestContext) -> Result<String, reqwest::Error> {
-        let client = Client::new();
+    fn do_request(&self, context: &RequestContext) -> Result<HttpResponse, PixelaClientError> {
+        let client = &self.client;
+        let url = context.url()?;
 
         let mut req = match context.method {
-            MethodType::Get => client.get(context.uri),
-            MethodType::Post => client.post(context.uri),
-            MethodType::Put => client.put(context.uri),
-            MethodType::Delete => client.delete(context.uri),
+            MethodType::Get => client.get(&url),
+            MethodType::Post => client.post(&url),
+            MethodType::Put => client.put(&url),
+            MethodType::Delete => client.delete(&url),
         };
 
-        if let Some(v) = context.body {
+        if let Some(ref v) = context.body {
             req = req.body(v.to_owned());
         };
 
-        let mut headers = HeaderMap::new();
-        for (key, val) in context.headers.iter() {
-            let type_name = match key {
-                HeaderType::UserToken => "X-USER-TOKEN",
-                HeaderType::ContentLength => "CONTENT-LENGTH",
-            };
+        let mut res = req.headers(context.header_map()?).send()?;
+        let status = res.status().as_u16();
+        let body = res.text()?;
+        Ok(HttpResponse { status, body })
+    }
+}
 
-            headers.insert(type_name, HeaderValue::from_str(val).unwrap());
-        }
+/// A future of a response from Pixela.
+pub type PixelaFuture<T> = Box<dyn Future<Item = T, Error = PixelaClientError> + Send>;
+
+/// A non-blocking transport used by `AsyncPixelaClient` to send requests to Pixela.
+///
+/// Implement this trait to inject your own transport, a recording client or a test double.
+pub trait AsyncHttpClient {
+    /// Sends the request and returns a future of the response.
+    ///
+    /// A response which is not success in HTTP status must be returned as `Ok`; it is classified by the caller.
+    fn do_request(&self, context: &RequestContext) -> PixelaFuture<HttpResponse>;
+}
+
+/// The default `AsyncHttpClient` built on the reqwest async client.
+///
+/// It holds a long-lived reqwest client, so connections are pooled and kept alive between requests.
+/// The returned futures must be run on a tokio runtime.
+#[derive(Debug, Clone)]
+pub struct TinyAsyncHttpClient {
+    client: AsyncClient,
+}
+
+impl TinyAsyncHttpClient {
+    /// Constructs a new `TinyAsyncHttpClient` with the default configuration.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when the TLS backend cannot be initialized.
+    pub fn new() -> Result<Self, PixelaClientError> {
+        TinyHttpClientBuilder::new().build_async()
+    }
+}
+
+impl AsyncHttpClient for TinyAsyncHttpClient {
+    fn do_request(&self, context: &RequestContext) -> PixelaFuture<HttpResponse> {
+        let client = &self.client;
+        let url = match context.url() {
+            Ok(v) => v,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        let mut req = match context.method {
+            MethodType::Get => client.get(&url),
+            MethodType::Post => client.post(&url),
+            MethodType::Put => client.put(&url),
+            MethodType::Delete => client.delete(&url),
+        };
+
+        if let Some(ref v) = context.body {
+            req = req.body(v.to_owned());
+        };
+
+        let headers = match context.header_map() {
+            Ok(v) => v,
+            Err(e) => return Box::new(future::err(e)),
+        };
 
         let res = req.headers(headers)
-                     .send()?
-                     .text()?;
-        Ok(res)
+                     .send()
+                     .and_then(|mut res| {
+                         let status = res.status().as_u16();
+                         res.text().map(move |body| HttpResponse { status, body })
+                     })
+                     .from_err();
+        Box::new(res)
     }
 }
\ No newline at end of file
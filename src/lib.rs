@@ -5,7 +5,7 @@
 //! # Example
 //! 
 //! ```rust,ignore
-//! use pixelast::{PixelaClient, ConsentAnswer, GraphType, GraphColor};
+//! use pixelast::{PixelaClient, ConsentAnswer, Graph, GraphUpdate, GraphType, GraphColor, SvgOptions};
 //! 
 //! fn main() {
 //!     let res = PixelaClient::create_new_user(
@@ -20,30 +20,34 @@
 //!         Err(v) => panic!("create new user failed. {}", v),
 //!     }
 //! 
-//!     let client = PixelaClient::new("username", "usertoken");
-//!     client.create_graph("graphid", "graphname", "cal", GraphType::Int, GraphColor::Shibafu).unwrap();
-//!     client.update_graph("graphid", "graphname", "cal", GraphColor::Shibafu).unwrap();
+//!     let client = PixelaClient::new("username", "usertoken").unwrap();
+//!     let graph_id = "graphid".parse().unwrap();
+//!     let graph = Graph::builder(&graph_id, "graphname", "cal", GraphType::Int, GraphColor::Shibafu).build();
+//!     client.create_graph(&graph).unwrap();
+//!
+//!     client.update_graph(&graph_id, &GraphUpdate::new().timezone("Asia/Tokyo")).unwrap();
+//!     println!("{:?}", client.get_graph(&graph_id).unwrap());
 //! 
-//!     client.record_pixel("graphid", "20181016", "10").unwrap();
-//!     client.record_pixel("graphid", "20181017", "10").unwrap();
-//!     client.record_pixel("graphid", "20181018", "10").unwrap();
+//!     client.record_pixel(&"graphid".parse().unwrap(), &"20181016".parse().unwrap(), &"10".parse().unwrap()).unwrap();
+//!     client.record_pixel(&"graphid".parse().unwrap(), &"20181017".parse().unwrap(), &"10".parse().unwrap()).unwrap();
+//!     client.record_pixel(&"graphid".parse().unwrap(), &"20181018".parse().unwrap(), &"10".parse().unwrap()).unwrap();
 //! 
-//!     client.update_pixel("graphid", "20181018", "20").unwrap();
-//!     client.delete_pixel("graphid", "20181016").unwrap();
+//!     client.update_pixel(&"graphid".parse().unwrap(), &"20181018".parse().unwrap(), &"20".parse().unwrap()).unwrap();
+//!     client.delete_pixel(&"graphid".parse().unwrap(), &"20181016".parse().unwrap()).unwrap();
 //! 
-//!     let pixel = client.get_pixel("graphid", "20181018").unwrap();
+//!     let pixel = client.get_pixel(&"graphid".parse().unwrap(), &"20181018".parse().unwrap()).unwrap();
 //!     println!("{:?}", pixel);
 //! 
-//!     client.increment("graphid").unwrap();
-//!     client.decrement("graphid").unwrap();
+//!     client.increment(&"graphid".parse().unwrap()).unwrap();
+//!     client.decrement(&"graphid".parse().unwrap()).unwrap();
 //! 
 //!     let graphs = client.get_graphs().unwrap();
 //!     println!("{:?}", graphs);
 //! 
-//!     let svg = client.get_graph_svg("graphid", Some("20181020")).unwrap();
+//!     let svg = client.get_graph_svg(&"graphid".parse().unwrap(), &SvgOptions::new().date(&"20181020".parse().unwrap())).unwrap();
 //!     println!("{}", svg);
 //! 
-//!     client.delete_graph("graphid").unwrap();
+//!     client.delete_graph(&"graphid".parse().unwrap()).unwrap();
 //!     client.delete_user().unwrap();
 //! }
 //! ```
@@ -55,63 +59,176 @@ extern crate serde_json;
 
 #[macro_use] extern crate serde_derive;
 #[macro_use] extern crate failure;
-
-use failure::Error;
+extern crate futures;
+extern crate tokio_timer;
+extern crate rand;
+#[cfg(feature = "chrono")] extern crate chrono;
+#[cfg(feature = "chrono")] extern crate chrono_tz;
 
 mod response;
 mod endpoint;
+mod api_call;
+mod retry;
+mod validation;
+mod types;
 mod http_client;
+mod async_client;
 mod error;
 mod graph;
 mod pixel;
 mod user;
+mod stopwatch;
+mod channel;
+mod notification;
+mod webhook;
+mod webhook_invoker;
 
-pub use self::user::ConsentAnswer;
-pub use self::graph::{Graph, GraphType, GraphColor};
-pub use self::pixel::Pixel;
-pub use self::error::PixelaClientError;
+pub use self::user::{ConsentAnswer, ProfileUpdate};
+pub use self::graph::{Graph, GraphBuilder, GraphUpdate, GraphStats, GraphType, GraphColor, SelfSufficient, SvgOptions, SvgMode, SvgAppearance, GraphHtmlMode, GraphUrls};
+pub use self::pixel::{Pixel, TodayPixel, PixelList, PixelBatchResult, MAX_PIXELS_PER_BATCH};
+pub use self::error::{PixelaClientError, ErrorKind};
+pub use self::http_client::{HttpClient, TinyHttpClient, TinyHttpClientBuilder, AsyncHttpClient, TinyAsyncHttpClient, RequestContext, HttpResponse, MethodType, HeaderType, PixelaFuture};
+pub use self::async_client::AsyncPixelaClient;
+pub use self::webhook_invoker::{WebhookInvoker, AsyncWebhookInvoker, WebhookInvokerBuilder};
+pub use self::retry::RetryPolicy;
+pub use self::stopwatch::LocalStopwatch;
+pub use self::channel::{Channel, ChannelDetail, SlackDetail};
+pub use self::notification::{NotificationRule, NotificationTarget, NotificationCondition};
+pub use self::webhook::{Webhook, WebhookType};
+pub use self::validation::{USERNAME_RULE, TOKEN_RULE, GRAPH_ID_RULE, PIXEL_DATE_RULE, QUANTITY_RULE, INT_QUANTITY_RULE, FLOAT_QUANTITY_RULE, PURGE_CACHE_URLS_RULE, BASE_URL_RULE, TIMEZONE_RULE, CHANNEL_ID_RULE, NOTIFICATION_ID_RULE, WEBHOOK_HASH_RULE, OPTIONAL_DATA_RULE};
+pub use self::types::{Username, Token, GraphId, PixelDate, Quantity, ChannelId, NotificationId, WebhookHash};
+pub use reqwest::Proxy;
 
 use self::user::CreateUserParam;
-use self::graph::UpdateGraphParam;
-use self::http_client::TinyHttpClient;
+use self::graph::GraphTypes;
+use self::endpoint::{Endpoint, DEFAULT_BASE_URL, DEFAULT_API_VERSION};
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
 
 /// A `PixelaClient` to request to Pixela with.
-pub struct PixelaClient {
+///
+/// Requests are sent by the `HttpClient` given as `C`, which is `TinyHttpClient` by default.
+///
+/// Quantities are checked against the type of the target graph before they are sent.
+/// The client learns the types from `get_graphs`, which it calls before the first write to a graph it does not know yet.
+/// A graph is looked up once until the next `get_graphs`, even when the lookup fails.
+/// Quantities read from Pixela are converted to the type of the graph when the client already knows it.
+pub struct PixelaClient<C = TinyHttpClient> {
     auth: Authentication,
+    endpoint: Endpoint,
+    http_client: C,
+    retry_policy: RetryPolicy,
+    graph_types: GraphTypes,
 }
 
-pub(crate) struct Authentication {
+/// A `PixelaClientBuilder` can be used to create a `PixelaClient` or an `AsyncPixelaClient` with custom configuration.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let client = PixelaClientBuilder::new("username", "usertoken")
+///     .base_url("http://localhost:8080")
+///     .api_version("v1")
+///     .http_client(TinyHttpClient::builder().timeout(Duration::from_secs(10)).build()?)
+///     .retry_policy(RetryPolicy::new(5))
+///     .build()?;
+/// ```
+pub struct PixelaClientBuilder<C = TinyHttpClient> {
     username: String,
     token: String,
+    base_url: String,
+    api_version: String,
+    http_client: Transport<C>,
+    retry_policy: RetryPolicy,
+}
+
+/// The transport of a builder. The default is built by `build`, and only when no other is given.
+pub(crate) enum Transport<C> {
+    Given(C),
+    Default(fn() -> Result<C, PixelaClientError>),
+}
+
+impl<C> Transport<C> {
+    pub(crate) fn build(self) -> Result<C, PixelaClientError> {
+        match self {
+            Transport::Given(v) => Ok(v),
+            Transport::Default(build) => build(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct Authentication {
+    username: Username,
+    token: Token,
 }
 
-impl PixelaClient {
+impl PixelaClient<TinyHttpClient> {
     /// Constructs a new `PixelaClient`.
     ///
     /// This method does not verify authentication.
-    pub fn new(username: &str, token: &str) -> Self {
-        Self {
-            auth: Authentication {
-                username: username.to_owned(),
-                token: token.to_owned(),
-            }
-        }
+    ///
+    /// # Errors
+    ///
+    /// This method fails when the username or the token violates the validation rule of Pixela,
+    /// or when the `TinyHttpClient` cannot be built.
+    pub fn new(username: &str, token: &str) -> Result<Self, PixelaClientError> {
+        PixelaClientBuilder::new(username, token).build()
+    }
+
+    /// Creates a `PixelaClientBuilder` to configure a `PixelaClient`.
+    pub fn builder(username: &str, token: &str) -> PixelaClientBuilder {
+        PixelaClientBuilder::new(username, token)
     }
 
     /// Create a new Pixela user.
     ///
     /// # Errors
     ///
+    /// This method fails when the username or the token is invalid or request not success in Pixela.
+    pub fn create_new_user(username: &str, token: &str, agree_terms_of_service: ConsentAnswer, not_minor: ConsentAnswer) -> Result<(), PixelaClientError> {
+        PixelaClient::new(username, token)?.create_user(agree_terms_of_service, not_minor)
+    }
+
+    /// Invoke a webhook of the user without the token, for example from a CI pipeline.
+    /// Use `WebhookInvoker` to invoke webhooks on another server or with another transport.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when the username is invalid or request not success in Pixela.
+    pub fn invoke_user_webhook(username: &str, webhook_hash: &WebhookHash) -> Result<(), PixelaClientError> {
+        WebhookInvoker::builder(username).build()?.invoke(webhook_hash)
+    }
+}
+
+impl<C> PixelaClient<C> where C: HttpClient {
+    /// Constructs a new `PixelaClient` which sends requests with the given `HttpClient`.
+    ///
+    /// This method does not verify authentication.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when the username or the token violates the validation rule of Pixela.
+    pub fn with_http_client(username: &str, token: &str, http_client: C) -> Result<Self, PixelaClientError> {
+        PixelaClientBuilder::new(username, token).http_client(http_client).build()
+    }
+
+    /// Create a new Pixela user with the username and token of this client.
+    ///
+    /// # Errors
+    ///
     /// This method fails when request not success in Pixela.
-    pub fn create_new_user(username: &str, token: &str, agree_terms_of_service: ConsentAnswer, not_minor: ConsentAnswer) -> Result<(), Error> {
+    pub fn create_user(&self, agree_terms_of_service: ConsentAnswer, not_minor: ConsentAnswer) -> Result<(), PixelaClientError> {
         let param = CreateUserParam {
-            username: username.to_owned(),
-            token: token.to_owned(),
+            username: self.auth.username.to_owned(),
+            token: self.auth.token.to_owned(),
             agree_terms_of_service,
             not_minor
-       };
+        };
 
-        user::create::<TinyHttpClient>(&param)
+        user::create(&self.endpoint, &param)?.send(&self.http_client, &self.retry_policy)
     }
 
     /// Updates the authentication token for the specified user.
@@ -119,8 +236,8 @@ impl PixelaClient {
     /// # Errors
     ///
     /// This method fails when request not success in Pixela.
-    pub fn update_user_token(&self, new_token: &str) -> Result<(), Error> {
-        user::update::<TinyHttpClient>(&self.auth, new_token)
+    pub fn update_user_token(&self, new_token: &Token) -> Result<(), PixelaClientError> {
+        user::update(&self.endpoint, &self.auth, new_token)?.send(&self.http_client, &self.retry_policy)
     }
 
     /// Deletes the specified registered user.
@@ -128,25 +245,59 @@ impl PixelaClient {
     /// # Errors
     ///
     /// This method fails when request not success in Pixela.
-    pub fn delete_user(&self) -> Result<(), Error> {
-        user::delete::<TinyHttpClient>(&self.auth)
+    pub fn delete_user(&self) -> Result<(), PixelaClientError> {
+        user::delete(&self.endpoint, &self.auth)?.send(&self.http_client, &self.retry_policy)
+    }
+
+    /// Update the public profile page of the user.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when request not success in Pixela.
+    pub fn update_profile(&self, update: &ProfileUpdate) -> Result<(), PixelaClientError> {
+        user::update_profile(&self.endpoint, &self.auth, update)?.send(&self.http_client, &self.retry_policy)
+    }
+
+    /// Get the public profile page of any user as HTML.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when request not success in Pixela.
+    pub fn get_profile_page(&self, username: &Username) -> Result<String, PixelaClientError> {
+        user::get_profile_page(&self.endpoint, username)?.send(&self.http_client, &self.retry_policy)
     }
 
     /// Create a new pixelation graph definition.
     ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let graph = Graph::builder(&graph_id, "graphname", "cal", GraphType::Int, GraphColor::Shibafu)
+    ///     .timezone("Asia/Tokyo")
+    ///     .build();
+    /// client.create_graph(&graph)?;
+    /// ```
+    ///
     /// # Errors
     ///
     /// This method fails when request not success in Pixela.
-    pub fn create_graph(&self, id: &str, name: &str, unit: &str, graph_type: GraphType, color: GraphColor) -> Result<(), Error> {
-        let param = Graph {
-            id: id.to_owned(),
-            name: name.to_owned(),
-            unit: unit.to_owned(),
-            graph_type,
-            color,
-        };
+    pub fn create_graph(&self, graph: &Graph) -> Result<(), PixelaClientError> {
+        graph::create(&self.endpoint, &self.auth, graph)?.send(&self.http_client, &self.retry_policy)?;
+        self.graph_types.insert(graph);
+
+        Ok(())
+    }
+
+    /// Get the pixelation graph definition.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when request not success in Pixela.
+    pub fn get_graph(&self, graph_id: &GraphId) -> Result<Graph, PixelaClientError> {
+        let graph = graph::get(&self.endpoint, &self.auth, graph_id)?.send(&self.http_client, &self.retry_policy)?;
+        self.graph_types.insert(&graph);
 
-        graph::create::<TinyHttpClient>(&self.auth, &param)
+        Ok(graph)
     }
 
     /// Get all predefined pixelation graph definitions.
@@ -154,8 +305,21 @@ impl PixelaClient {
     /// # Errors
     ///
     /// This method fails when request not success in Pixela.
-    pub fn get_graphs(&self) -> Result<Vec<Graph>, Error> {
-        graph::get_all::<TinyHttpClient>(&self.auth)
+    pub fn get_graphs(&self) -> Result<Vec<Graph>, PixelaClientError> {
+        let graphs = graph::get_all(&self.endpoint, &self.auth)?.send(&self.http_client, &self.retry_policy)?;
+        self.graph_types.reset(&graphs);
+
+        Ok(graphs)
+    }
+
+    /// Get the statistics of the graph, such as the total, the maximum and the average of the quantities.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when request not success in Pixela.
+    pub fn get_graph_stats(&self, graph_id: &GraphId) -> Result<GraphStats, PixelaClientError> {
+        let graph_type = self.graph_types.get(graph_id);
+        graph::get_stats(&self.endpoint, &self.auth, graph_id, graph_type)?.send(&self.http_client, &self.retry_policy)
     }
 
     /// Based on the registered information, express the graph in SVG format diagram.
@@ -163,23 +327,44 @@ impl PixelaClient {
     /// # Errors
     ///
     /// This method fails when request not success in Pixela.
-    pub fn get_graph_svg(&self, graph_id: &str, date: Option<&str>) -> Result<String, Error> {
-        graph::get_graph_svg::<TinyHttpClient>(&self.auth, graph_id, date)
+    pub fn get_graph_svg(&self, graph_id: &GraphId, options: &SvgOptions) -> Result<String, PixelaClientError> {
+        graph::get_graph_svg(&self.endpoint, &self.auth, graph_id, options)?.send(&self.http_client, &self.retry_policy)
     }
 
-    /// Update predefined pixelation graph definitions. The items that can be updated are limited as compared with the pixelation graph definition creation.
+    /// Get the detail page of the graph as HTML.
     ///
     /// # Errors
     ///
     /// This method fails when request not success in Pixela.
-    pub fn update_graph(&self, graph_id: &str, name: &str, unit: &str, color: GraphColor) -> Result<(), Error> {
-        let param = UpdateGraphParam {
-            name: name.to_owned(),
-            unit: unit.to_owned(),
-            color,
-        };
+    pub fn get_graph_html(&self, graph_id: &GraphId, mode: GraphHtmlMode) -> Result<String, PixelaClientError> {
+        graph::get_graph_html(&self.endpoint, &self.auth, graph_id, mode)?.send(&self.http_client, &self.retry_policy)
+    }
+
+    /// Returns the public URLs of the graph without sending a request.
+    pub fn graph_url(&self, graph_id: &GraphId) -> GraphUrls {
+        graph::urls(&self.endpoint, &self.auth, graph_id)
+    }
+
+    /// Update predefined pixelation graph definitions. Only the fields set in the `GraphUpdate` are changed.
+    /// Use `GraphUpdate::from(&graph)` to send every updatable field of a `Graph`.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when request not success in Pixela.
+    pub fn update_graph(&self, graph_id: &GraphId, update: &GraphUpdate) -> Result<(), PixelaClientError> {
+        graph::update(&self.endpoint, &self.auth, graph_id, update)?.send(&self.http_client, &self.retry_policy)?;
+        self.graph_types.remove(graph_id);
 
-        graph::update::<TinyHttpClient>(&self.auth, graph_id, &param)
+        Ok(())
+    }
+
+    /// Make Pixela purge the caches of the graph at its purge cache URLs, for example after `record_pixels`.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when request not success in Pixela.
+    pub fn purge_graph_cache(&self, graph_id: &GraphId) -> Result<(), PixelaClientError> {
+        graph::purge_cache(&self.endpoint, &self.auth, graph_id)?.send(&self.http_client, &self.retry_policy)
     }
 
     /// Delete the predefined pixelation graph definition.
@@ -187,45 +372,122 @@ impl PixelaClient {
     /// # Errors
     ///
     /// This method fails when request not success in Pixela.
-    pub fn delete_graph(&self, graph_id: &str) -> Result<(), Error> {
-        graph::delete::<TinyHttpClient>(&self.auth, graph_id)
+    pub fn delete_graph(&self, graph_id: &GraphId) -> Result<(), PixelaClientError> {
+        graph::delete(&self.endpoint, &self.auth, graph_id)?.send(&self.http_client, &self.retry_policy)?;
+        self.graph_types.remove(graph_id);
+
+        Ok(())
     }
 
     /// It records the quantity of the specified date as a "Pixel".
     ///
     /// # Errors
     ///
+    /// This method fails when the quantity does not match the graph type or request not success in Pixela.
+    pub fn record_pixel(&self, graph_id: &GraphId, date: &PixelDate, quantity: &Quantity) -> Result<(), PixelaClientError> {
+        self.create_pixel(graph_id, &Pixel::new(date, quantity))
+    }
+
+    /// It records the quantity of the specified date as a "Pixel" with the data serialized into its optional data.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when the JSON of the data is larger than 10KB, the quantity does not match the graph type or request not success in Pixela.
+    pub fn record_pixel_with_data<D>(&self, graph_id: &GraphId, date: &PixelDate, quantity: &Quantity, data: &D) -> Result<(), PixelaClientError> where D: Serialize {
+        self.create_pixel(graph_id, &Pixel::with_optional_data(date, quantity, data)?)
+    }
+
+    /// It records the pixels at once, sending them in chunks of `MAX_PIXELS_PER_BATCH` pixels.
+    /// A failed chunk does not stop the following chunks, and the result of every chunk is returned in order.
+    /// Call `purge_graph_cache` afterwards to refresh the cached graph images.
+    /// Nothing is sent when `pixels` is empty.
+    pub fn record_pixels(&self, graph_id: &GraphId, pixels: &[Pixel]) -> Vec<PixelBatchResult> {
+        if pixels.is_empty() {
+            return Vec::new();
+        }
+
+        let graph_type = self.graph_type(graph_id);
+        pixel::batch_ranges(pixels.len()).into_iter()
+            .map(|range| {
+                let result = pixel::create_batch(&self.endpoint, &self.auth, graph_id, &pixels[range.clone()], graph_type)
+                    .and_then(|v| v.send(&self.http_client, &self.retry_policy));
+
+                PixelBatchResult { range, result }
+            })
+            .collect()
+    }
+
+    /// Get registered quantity as "Pixel". The quantity has the type of the graph when the client knows it.
+    ///
+    /// # Errors
+    ///
     /// This method fails when request not success in Pixela.
-    pub fn record_pixel(&self, graph_id: &str, date: &str, quantity: &str) -> Result<(), Error> {
-        let param = Pixel {
-            date: date.to_owned(),
-            quantity: quantity.to_owned(),
-        };
+    pub fn get_pixel(&self, graph_id: &GraphId, date: &PixelDate) -> Result<Pixel, PixelaClientError> {
+        let graph_type = self.graph_types.get(graph_id);
+        pixel::get(&self.endpoint, &self.auth, graph_id, date, graph_type)?.send(&self.http_client, &self.retry_policy)
+    }
+
+    /// Get registered quantity as "Pixel" with its optional data deserialized into `D`.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when the optional data can not be deserialized into `D` or request not success in Pixela.
+    pub fn get_pixel_with_data<D>(&self, graph_id: &GraphId, date: &PixelDate) -> Result<(Pixel, Option<D>), PixelaClientError> where D: DeserializeOwned {
+        let pixel = self.get_pixel(graph_id, date)?;
+        let data = pixel.optional_data()?;
 
-        pixel::create::<TinyHttpClient>(&self.auth, graph_id, &param)
+        Ok((pixel, data))
     }
 
-    /// Get registered quantity as "Pixel".
+    /// Get the latest recorded pixel of the graph, or `None` when the graph has no pixel.
     ///
     /// # Errors
     ///
     /// This method fails when request not success in Pixela.
-    pub fn get_pixel(&self, graph_id: &str, date: &str) -> Result<Pixel, Error> {
-        pixel::get::<TinyHttpClient>(&self.auth, graph_id, date)
+    pub fn get_latest_pixel(&self, graph_id: &GraphId) -> Result<Option<Pixel>, PixelaClientError> {
+        let graph_type = self.graph_types.get(graph_id);
+        pixel::get_latest(&self.endpoint, &self.auth, graph_id, graph_type)?.send(&self.http_client, &self.retry_policy)
     }
 
-    /// Update the quantity already registered as a "Pixel".
+    /// Get the pixel of today in the timezone of the graph, or `None` when nothing is recorded today.
+    /// With `return_empty`, Pixela answers an empty day with a zero quantity instead of an error.
+    /// Pixela does not return the date of the pixel.
     ///
     /// # Errors
     ///
     /// This method fails when request not success in Pixela.
-    pub fn update_pixel(&self, graph_id: &str, date: &str, quantity: &str) -> Result<(), Error> {
-        let param = Pixel {
-            date: date.to_owned(),
-            quantity: quantity.to_owned(),
-        };
+    pub fn get_today_pixel(&self, graph_id: &GraphId, return_empty: bool) -> Result<Option<TodayPixel>, PixelaClientError> {
+        let graph_type = self.graph_types.get(graph_id);
+        pixel::get_today(&self.endpoint, &self.auth, graph_id, return_empty, graph_type)?.send(&self.http_client, &self.retry_policy)
+    }
+
+    /// Get the pixels recorded between `from` and `to`, which Pixela limits to a year.
+    /// Only the dates are returned unless `with_body` is set, in which case the quantities and optional data are included.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when request not success in Pixela.
+    pub fn list_pixels(&self, graph_id: &GraphId, from: Option<&PixelDate>, to: Option<&PixelDate>, with_body: bool) -> Result<PixelList, PixelaClientError> {
+        let graph_type = if with_body { self.graph_types.get(graph_id) } else { None };
+        pixel::list(&self.endpoint, &self.auth, graph_id, from, to, with_body, graph_type)?.send(&self.http_client, &self.retry_policy)
+    }
+
+    /// Update the quantity already registered as a "Pixel".
+    ///
+    /// # Errors
+    ///
+    /// This method fails when the quantity does not match the graph type or request not success in Pixela.
+    pub fn update_pixel(&self, graph_id: &GraphId, date: &PixelDate, quantity: &Quantity) -> Result<(), PixelaClientError> {
+        self.put_pixel(graph_id, &Pixel::new(date, quantity))
+    }
 
-        pixel::update::<TinyHttpClient>(&self.auth, graph_id, &param)
+    /// Update the quantity and the optional data already registered as a "Pixel".
+    ///
+    /// # Errors
+    ///
+    /// This method fails when the JSON of the data is larger than 10KB, the quantity does not match the graph type or request not success in Pixela.
+    pub fn update_pixel_with_data<D>(&self, graph_id: &GraphId, date: &PixelDate, quantity: &Quantity, data: &D) -> Result<(), PixelaClientError> where D: Serialize {
+        self.put_pixel(graph_id, &Pixel::with_optional_data(date, quantity, data)?)
     }
 
     /// Delete the registered "Pixel".
@@ -233,8 +495,74 @@ impl PixelaClient {
     /// # Errors
     ///
     /// This method fails when request not success in Pixela.
-    pub fn delete_pixel(&self, graph_id: &str, date: &str) -> Result<(), Error> {
-        pixel::delete::<TinyHttpClient>(&self.auth, graph_id, date)
+    pub fn delete_pixel(&self, graph_id: &GraphId, date: &PixelDate) -> Result<(), PixelaClientError> {
+        pixel::delete(&self.endpoint, &self.auth, graph_id, date)?.send(&self.http_client, &self.retry_policy)
+    }
+
+    /// Add the quantity to "Pixel" of the day in the timezone of the graph.
+    /// It is never retried unless Pixela rejected it, so that the quantity is not added twice.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when the quantity does not match the graph type or request not success in Pixela.
+    pub fn add(&self, graph_id: &GraphId, quantity: &Quantity) -> Result<(), PixelaClientError> {
+        let graph_type = self.graph_type(graph_id);
+        pixel::add(&self.endpoint, &self.auth, graph_id, quantity, graph_type)?.send(&self.http_client, &self.retry_policy)
+    }
+
+    /// Subtract the quantity from "Pixel" of the day in the timezone of the graph.
+    /// It is never retried unless Pixela rejected it, so that the quantity is not subtracted twice.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when the quantity does not match the graph type or request not success in Pixela.
+    pub fn subtract(&self, graph_id: &GraphId, quantity: &Quantity) -> Result<(), PixelaClientError> {
+        let graph_type = self.graph_type(graph_id);
+        pixel::subtract(&self.endpoint, &self.auth, graph_id, quantity, graph_type)?.send(&self.http_client, &self.retry_policy)
+    }
+
+    /// Starts the stopwatch of the graph in Pixela when it is not running, or stops it otherwise,
+    /// which records the elapsed minutes to the pixel of the day.
+    /// Pixela does not tell whether the stopwatch is running.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when request not success in Pixela.
+    pub fn toggle_stopwatch(&self, graph_id: &GraphId) -> Result<(), PixelaClientError> {
+        pixel::stopwatch(&self.endpoint, &self.auth, graph_id)?.send(&self.http_client, &self.retry_policy)
+    }
+
+    /// Toggles the stopwatch of the graph in Pixela, and starts the `LocalStopwatch` when Pixela is unavailable.
+    /// The stopwatch in Pixela is stopped instead when it is already running.
+    /// After a timeout or a server error Pixela may have toggled the stopwatch anyway,
+    /// so the minutes can be recorded by both stopwatches. Check the pixel of the day in that case.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when request not success in Pixela for other reasons, or the `LocalStopwatch` can not be started.
+    pub fn toggle_stopwatch_or_start_local(&self, graph_id: &GraphId, local: &LocalStopwatch) -> Result<(), PixelaClientError> {
+        match self.toggle_stopwatch(graph_id) {
+            Err(ref e) if e.is_unavailable() => local.start(),
+            res => res,
+        }
+    }
+
+    /// Stops the `LocalStopwatch` and records the elapsed minutes with `add` when it is running,
+    /// or toggles the stopwatch of the graph in Pixela otherwise.
+    /// The `LocalStopwatch` keeps running when the minutes could not be recorded, so that they are not lost.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when request not success in Pixela or the `LocalStopwatch` can not be read.
+    pub fn stop_local_or_toggle_stopwatch(&self, graph_id: &GraphId, local: &LocalStopwatch) -> Result<(), PixelaClientError> {
+        match local.elapsed_minutes()? {
+            None => self.toggle_stopwatch(graph_id),
+            Some(0) => local.reset(),
+            Some(minutes) => {
+                self.add(graph_id, &Quantity::Int(minutes as i64))?;
+                local.reset()
+            },
+        }
     }
 
     /// Increment quantity "Pixel" of the day (UTC).
@@ -243,8 +571,8 @@ impl PixelaClient {
     /// # Errors
     ///
     /// This method fails when request not success in Pixela.
-    pub fn increment(&self, graph_id: &str) -> Result<(), Error> {
-        pixel::increment::<TinyHttpClient>(&self.auth, graph_id)
+    pub fn increment(&self, graph_id: &GraphId) -> Result<(), PixelaClientError> {
+        pixel::increment(&self.endpoint, &self.auth, graph_id)?.send(&self.http_client, &self.retry_policy)
     }
 
     /// Decrement quantity "Pixel" of the day (UTC).
@@ -253,7 +581,666 @@ impl PixelaClient {
     /// # Errors
     ///
     /// This method fails when request not success in Pixela.
-    pub fn decrement(&self, graph_id: &str) -> Result<(), Error> {
-        pixel::decrement::<TinyHttpClient>(&self.auth, graph_id)
+    pub fn decrement(&self, graph_id: &GraphId) -> Result<(), PixelaClientError> {
+        pixel::decrement(&self.endpoint, &self.auth, graph_id)?.send(&self.http_client, &self.retry_policy)
+    }
+
+    /// Create a notification channel.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when request not success in Pixela.
+    pub fn create_channel(&self, channel: &Channel) -> Result<(), PixelaClientError> {
+        channel::create(&self.endpoint, &self.auth, channel)?.send(&self.http_client, &self.retry_policy)
+    }
+
+    /// Get all notification channels of the user.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when request not success in Pixela.
+    pub fn get_channels(&self) -> Result<Vec<Channel>, PixelaClientError> {
+        channel::get_all(&self.endpoint, &self.auth)?.send(&self.http_client, &self.retry_policy)
+    }
+
+    /// Update the name and the settings of the channel with the same id.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when request not success in Pixela.
+    pub fn update_channel(&self, channel: &Channel) -> Result<(), PixelaClientError> {
+        channel::update(&self.endpoint, &self.auth, channel)?.send(&self.http_client, &self.retry_policy)
+    }
+
+    /// Delete a notification channel.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when request not success in Pixela.
+    pub fn delete_channel(&self, channel_id: &ChannelId) -> Result<(), PixelaClientError> {
+        channel::delete(&self.endpoint, &self.auth, channel_id)?.send(&self.http_client, &self.retry_policy)
+    }
+
+    /// Create a notification rule of the graph. The threshold has the type of the graph.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when the threshold does not match the type of the graph or request not success in Pixela.
+    pub fn create_notification(&self, graph_id: &GraphId, rule: &NotificationRule) -> Result<(), PixelaClientError> {
+        let graph_type = self.graph_type(graph_id);
+        notification::create(&self.endpoint, &self.auth, graph_id, rule, graph_type)?.send(&self.http_client, &self.retry_policy)
+    }
+
+    /// Get all notification rules of the graph.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when request not success in Pixela.
+    pub fn get_notifications(&self, graph_id: &GraphId) -> Result<Vec<NotificationRule>, PixelaClientError> {
+        notification::get_all(&self.endpoint, &self.auth, graph_id)?.send(&self.http_client, &self.retry_policy)
+    }
+
+    /// Update the notification rule of the graph with the same id.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when the threshold does not match the type of the graph or request not success in Pixela.
+    pub fn update_notification(&self, graph_id: &GraphId, rule: &NotificationRule) -> Result<(), PixelaClientError> {
+        let graph_type = self.graph_type(graph_id);
+        notification::update(&self.endpoint, &self.auth, graph_id, rule, graph_type)?.send(&self.http_client, &self.retry_policy)
+    }
+
+    /// Delete a notification rule of the graph.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when request not success in Pixela.
+    pub fn delete_notification(&self, graph_id: &GraphId, notification_id: &NotificationId) -> Result<(), PixelaClientError> {
+        notification::delete(&self.endpoint, &self.auth, graph_id, notification_id)?.send(&self.http_client, &self.retry_policy)
+    }
+
+    /// Create a webhook which updates the graph when it is invoked, and return its hash.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when request not success in Pixela.
+    pub fn create_webhook(&self, graph_id: &GraphId, webhook_type: WebhookType) -> Result<WebhookHash, PixelaClientError> {
+        webhook::create(&self.endpoint, &self.auth, graph_id, webhook_type)?.send(&self.http_client, &self.retry_policy)
+    }
+
+    /// Get all webhooks of the user.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when request not success in Pixela.
+    pub fn get_webhooks(&self) -> Result<Vec<Webhook>, PixelaClientError> {
+        webhook::get_all(&self.endpoint, &self.auth)?.send(&self.http_client, &self.retry_policy)
+    }
+
+    /// Invoke a webhook of the user. The token is not sent.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when request not success in Pixela.
+    pub fn invoke_webhook(&self, webhook_hash: &WebhookHash) -> Result<(), PixelaClientError> {
+        webhook::invoke(&self.endpoint, &self.auth.username, webhook_hash)?.send(&self.http_client, &self.retry_policy)
+    }
+
+    /// Delete a webhook.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when request not success in Pixela.
+    pub fn delete_webhook(&self, webhook_hash: &WebhookHash) -> Result<(), PixelaClientError> {
+        webhook::delete(&self.endpoint, &self.auth, webhook_hash)?.send(&self.http_client, &self.retry_policy)
+    }
+
+    fn create_pixel(&self, graph_id: &GraphId, param: &Pixel) -> Result<(), PixelaClientError> {
+        let graph_type = self.graph_type(graph_id);
+        pixel::create(&self.endpoint, &self.auth, graph_id, param, graph_type)?.send(&self.http_client, &self.retry_policy)
+    }
+
+    fn put_pixel(&self, graph_id: &GraphId, param: &Pixel) -> Result<(), PixelaClientError> {
+        let graph_type = self.graph_type(graph_id);
+        pixel::update(&self.endpoint, &self.auth, graph_id, param, graph_type)?.send(&self.http_client, &self.retry_policy)
+    }
+
+    /// Returns the type of the graph to write to, fetching the graph definitions when it is not known yet.
+    /// It is `None` when the graph does not exist or the definitions can not be fetched, leaving Pixela to validate the quantity.
+    /// Either way, the graph is not looked up again until the next `get_graphs`.
+    fn graph_type(&self, graph_id: &GraphId) -> Option<GraphType> {
+        if let Some(v) = self.graph_types.lookup(graph_id) {
+            return v;
+        }
+
+        let _ = self.get_graphs();
+        self.graph_types.insert_missing(graph_id);
+        self.graph_types.get(graph_id)
+    }
+}
+
+impl PixelaClientBuilder<TinyHttpClient> {
+    /// Constructs a new `PixelaClientBuilder` targeting the Pixela production server.
+    pub fn new(username: &str, token: &str) -> Self {
+        Self {
+            username: username.to_owned(),
+            token: token.to_owned(),
+            base_url: DEFAULT_BASE_URL.to_owned(),
+            api_version: DEFAULT_API_VERSION.to_owned(),
+            http_client: Transport::Default(|| TinyHttpClient::builder().build()),
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+}
+
+impl<C> PixelaClientBuilder<C> {
+    /// Sets the base url of the Pixela server. Default is `https://pixe.la`.
+    pub fn base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.to_owned();
+        self
+    }
+
+    /// Sets the api version prefix appended to the base url. Default is `v1`.
+    pub fn api_version(mut self, api_version: &str) -> Self {
+        self.api_version = api_version.to_owned();
+        self
+    }
+
+    /// Sets the `HttpClient` or `AsyncHttpClient` used to send requests. Default is `TinyHttpClient`.
+    pub fn http_client<D>(self, http_client: D) -> PixelaClientBuilder<D> {
+        self.transport(Transport::Given(http_client))
+    }
+
+    /// Sets the transport built by `build` when no other is given.
+    pub(crate) fn default_http_client<D>(self, build: fn() -> Result<D, PixelaClientError>) -> PixelaClientBuilder<D> {
+        self.transport(Transport::Default(build))
+    }
+
+    fn transport<D>(self, http_client: Transport<D>) -> PixelaClientBuilder<D> {
+        PixelaClientBuilder {
+            username: self.username,
+            token: self.token,
+            base_url: self.base_url,
+            api_version: self.api_version,
+            http_client,
+            retry_policy: self.retry_policy,
+        }
+    }
+
+    /// Sets the `RetryPolicy` of rejected and failed requests. Default is `RetryPolicy::default()`, which sends a request at most 3 times.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Returns a `PixelaClient` that uses this `PixelaClientBuilder` configuration.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when the username or the token violates the validation rule of Pixela,
+    /// when the base url is not an absolute http or https URL, or when the default `TinyHttpClient` cannot be built.
+    pub fn build(self) -> Result<PixelaClient<C>, PixelaClientError> where C: HttpClient {
+        Ok(PixelaClient {
+            auth: self.auth()?,
+            endpoint: self.endpoint()?,
+            http_client: self.http_client.build()?,
+            retry_policy: self.retry_policy,
+            graph_types: GraphTypes::default(),
+        })
+    }
+
+    /// Returns an `AsyncPixelaClient` that uses this `PixelaClientBuilder` configuration.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when the username or the token violates the validation rule of Pixela,
+    /// when the base url is not an absolute http or https URL, or when the default `TinyAsyncHttpClient` cannot be built.
+    pub fn build_async(self) -> Result<AsyncPixelaClient<C>, PixelaClientError> where C: AsyncHttpClient + Send + Sync + 'static {
+        Ok(AsyncPixelaClient {
+            auth: self.auth()?,
+            endpoint: self.endpoint()?,
+            http_client: Arc::new(self.http_client.build()?),
+            retry_policy: self.retry_policy,
+            graph_types: Arc::new(GraphTypes::default()),
+        })
+    }
+
+    fn auth(&self) -> Result<Authentication, PixelaClientError> {
+        Ok(Authentication {
+            username: Username::new(&self.username)?,
+            token: Token::new(&self.token)?,
+        })
+    }
+
+    fn endpoint(&self) -> Result<Endpoint, PixelaClientError> {
+        validation::validate_base_url(&self.base_url)?;
+        Ok(Endpoint::new(&self.base_url, &self.api_version))
+    }
+}
+
+#[cfg(test)]
+mod lib_test {
+    use super::*;
+    use std::cell::RefCell;
+    use std::time::Duration;
+
+    /// Returns the responses in order and repeats the last one.
+    struct RecordingHttpClient {
+        uris: RefCell<Vec<String>>,
+        responses: RefCell<Vec<HttpResponse>>,
+    }
+
+    impl RecordingHttpClient {
+        fn new(responses: Vec<HttpResponse>) -> Self {
+            Self {
+                uris: RefCell::new(Vec::new()),
+                responses: RefCell::new(responses),
+            }
+        }
+    }
+
+    impl HttpClient for RecordingHttpClient {
+        fn do_request(&self, context: &RequestContext) -> Result<HttpResponse, PixelaClientError> {
+            self.uris.borrow_mut().push(context.url().unwrap());
+
+            let mut responses = self.responses.borrow_mut();
+            if responses.len() > 1 {
+                return Ok(responses.remove(0));
+            }
+            Ok(responses[0].clone())
+        }
+    }
+
+    fn success() -> HttpResponse {
+        HttpResponse::new(200, r#"{"message":"Success.","isSuccess":true}"#)
+    }
+
+    fn graphs() -> HttpResponse {
+        HttpResponse::new(200, r#"{"graphs":[{"id":"testid","name":"test","unit":"commit","type":"int","color":"shibafu"}]}"#)
+    }
+
+    fn test_client(responses: Vec<HttpResponse>) -> PixelaClient<RecordingHttpClient> {
+        PixelaClient::builder("testuser", "testtoken")
+            .http_client(RecordingHttpClient::new(responses))
+            .retry_policy(RetryPolicy::new(3).base_delay(Duration::from_millis(0)))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn custom_http_client_test() {
+        let client = PixelaClient::builder("testuser", "testtoken")
+            .base_url("http://localhost:8080")
+            .http_client(RecordingHttpClient::new(vec![success()]))
+            .build()
+            .unwrap();
+
+        client.delete_graph(&GraphId::new("testid").unwrap()).unwrap();
+        assert_eq!(*client.http_client.uris.borrow(), vec!["http://localhost:8080/v1/users/testuser/graphs/testid"]);
+    }
+
+    #[test]
+    fn invalid_base_url_test() {
+        let result = PixelaClient::builder("testuser", "testtoken")
+            .base_url("localhost:8080")
+            .http_client(RecordingHttpClient::new(vec![success()]))
+            .build();
+        match result {
+            Err(PixelaClientError::InvalidParameter { name, rule }) => {
+                assert_eq!(name, "base url");
+                assert_eq!(rule, BASE_URL_RULE);
+            },
+            _ => panic!("the base url must be rejected"),
+        }
+    }
+
+    #[test]
+    fn request_not_success_test() {
+        let client = test_client(vec![HttpResponse::new(404, r#"{"message":"Specified pixel not found.","isSuccess":false}"#)]);
+
+        let err = client.get_pixel(&GraphId::new("testid").unwrap(), &PixelDate::new("20181018").unwrap()).unwrap_err();
+        assert_eq!(err.kind(), Some(ErrorKind::PixelNotFound));
+        assert_eq!(err.status(), Some(404));
+        assert_eq!(client.http_client.uris.borrow().len(), 1);
+    }
+
+    #[test]
+    fn invalid_token_test() {
+        match PixelaClient::new("testuser", "test\ntoken") {
+            Err(PixelaClientError::InvalidParameter { name, .. }) => assert_eq!(name, "token"),
+            _ => panic!("not failed."),
+        };
+
+        match PixelaClient::new("test_user", "testtoken") {
+            Err(PixelaClientError::InvalidParameter { name, .. }) => assert_eq!(name, "username"),
+            _ => panic!("not failed."),
+        };
+    }
+
+    #[test]
+    fn retry_rejected_request_test() {
+        let rejected = HttpResponse::new(503, r#"{"message":"Please retry this request.","isSuccess":false,"isRejected":true}"#);
+        let client = test_client(vec![rejected.clone(), rejected, success()]);
+
+        client.increment(&GraphId::new("testid").unwrap()).unwrap();
+        assert_eq!(client.http_client.uris.borrow().len(), 3);
+    }
+
+    #[test]
+    fn retry_server_error_test() {
+        let server_error = HttpResponse::new(500, r#"{"message":"Internal server error.","isSuccess":false}"#);
+
+        let client = test_client(vec![graphs(), server_error.clone(), success()]);
+        client.record_pixel(&GraphId::new("testid").unwrap(), &PixelDate::new("20181018").unwrap(), &Quantity::Int(5)).unwrap();
+        assert_eq!(client.http_client.uris.borrow().len(), 3);
+
+        let client = test_client(vec![server_error, success()]);
+        let err = client.increment(&GraphId::new("testid").unwrap()).unwrap_err();
+        assert_eq!(err.kind(), Some(ErrorKind::ServerError));
+        assert_eq!(client.http_client.uris.borrow().len(), 1);
+    }
+
+    #[test]
+    fn not_json_server_error_test() {
+        let bad_gateway = HttpResponse::new(502, "<html>Bad Gateway</html>");
+        let graph_id = GraphId::new("testid").unwrap();
+
+        let client = test_client(vec![bad_gateway.clone(), HttpResponse::new(200, "<svg></svg>")]);
+        assert_eq!(client.get_graph_svg(&graph_id, &SvgOptions::new()).unwrap(), "<svg></svg>");
+        assert_eq!(client.http_client.uris.borrow().len(), 2);
+
+        let client = test_client(vec![bad_gateway]);
+        let err = client.get_graph_svg(&graph_id, &SvgOptions::new()).unwrap_err();
+        assert_eq!(err.status(), Some(502));
+        assert_eq!(err.kind(), Some(ErrorKind::ServerError));
+        assert_eq!(client.delete_graph(&graph_id).unwrap_err().status(), Some(502));
+    }
+
+    #[test]
+    fn non_idempotent_test() {
+        let server_error = HttpResponse::new(500, r#"{"message":"Internal server error.","isSuccess":false}"#);
+        let client = test_client(vec![server_error]);
+        let graph_id = GraphId::new("testid").unwrap();
+
+        // Each call is sent once, and not retried after the server error.
+        assert!(client.delete_graph(&graph_id).is_err());
+        assert!(client.update_user_token(&Token::new("newtoken").unwrap()).is_err());
+        assert!(client.delete_user().is_err());
+        assert!(client.delete_pixel(&graph_id, &PixelDate::new("20181018").unwrap()).is_err());
+        assert!(client.delete_channel(&ChannelId::new("testchannel").unwrap()).is_err());
+        assert!(client.delete_notification(&graph_id, &NotificationId::new("testrule").unwrap()).is_err());
+        assert!(client.delete_webhook(&WebhookHash::new("0a1b2c").unwrap()).is_err());
+        assert_eq!(*client.http_client.uris.borrow(), vec![
+            "https://pixe.la/v1/users/testuser/graphs/testid",
+            "https://pixe.la/v1/users/testuser",
+            "https://pixe.la/v1/users/testuser",
+            "https://pixe.la/v1/users/testuser/graphs/testid/20181018",
+            "https://pixe.la/v1/users/testuser/channels/testchannel",
+            "https://pixe.la/v1/users/testuser/graphs/testid/notifications/testrule",
+            "https://pixe.la/v1/users/testuser/webhooks/0a1b2c",
+        ]);
+    }
+
+    #[test]
+    fn quantity_graph_type_test() {
+        let client = test_client(vec![graphs(), success()]);
+        let graph_id = GraphId::new("testid").unwrap();
+        let date = PixelDate::new("20181018").unwrap();
+
+        match client.record_pixel(&graph_id, &date, &Quantity::Float(1.5)) {
+            Err(PixelaClientError::InvalidParameter { rule, .. }) => assert_eq!(rule, INT_QUANTITY_RULE),
+            _ => panic!("not failed."),
+        };
+        assert_eq!(*client.http_client.uris.borrow(), vec!["https://pixe.la/v1/users/testuser/graphs"]);
+
+        client.record_pixel(&graph_id, &date, &Quantity::Int(5)).unwrap();
+        assert_eq!(client.http_client.uris.borrow().len(), 2);
+    }
+
+    #[test]
+    fn graph_type_lookup_test() {
+        let missing = GraphId::new("missing").unwrap();
+        let date = PixelDate::new("20181018").unwrap();
+
+        let client = test_client(vec![graphs(), success()]);
+        client.record_pixel(&missing, &date, &Quantity::Int(5)).unwrap();
+        client.record_pixel(&missing, &date, &Quantity::Int(5)).unwrap();
+        assert_eq!(*client.http_client.uris.borrow(), vec![
+            "https://pixe.la/v1/users/testuser/graphs",
+            "https://pixe.la/v1/users/testuser/graphs/missing",
+            "https://pixe.la/v1/users/testuser/graphs/missing",
+        ]);
+
+        let not_found = HttpResponse::new(404, r#"{"message":"User `testuser` does not exist or the token is wrong.","isSuccess":false}"#);
+        let client = test_client(vec![not_found, success()]);
+        let graph_id = GraphId::new("testid").unwrap();
+        client.record_pixel(&graph_id, &date, &Quantity::Int(5)).unwrap();
+        client.record_pixel(&graph_id, &date, &Quantity::Int(5)).unwrap();
+        assert_eq!(*client.http_client.uris.borrow(), vec![
+            "https://pixe.la/v1/users/testuser/graphs",
+            "https://pixe.la/v1/users/testuser/graphs/testid",
+            "https://pixe.la/v1/users/testuser/graphs/testid",
+        ]);
+    }
+
+    #[test]
+    fn optional_data_test() {
+        let client = test_client(vec![HttpResponse::new(200, r#"{"quantity":"5","optionalData":"[1,2]"}"#)]);
+        let graph_id = GraphId::new("testid").unwrap();
+        let date = PixelDate::new("20181018").unwrap();
+
+        match client.record_pixel_with_data(&graph_id, &date, &Quantity::Int(5), &"a".repeat(10240)) {
+            Err(PixelaClientError::InvalidParameter { rule, .. }) => assert_eq!(rule, OPTIONAL_DATA_RULE),
+            _ => panic!("not failed."),
+        };
+        assert_eq!(client.http_client.uris.borrow().len(), 0);
+
+        let (pixel, data) = client.get_pixel_with_data::<Vec<i32>>(&graph_id, &date).unwrap();
+        assert_eq!(pixel.quantity, Quantity::Int(5));
+        assert_eq!(data, Some(vec![1, 2]));
+    }
+
+    #[test]
+    fn get_graph_test() {
+        let graph_def = HttpResponse::new(200, r#"{"id":"testid","name":"test","unit":"kg","type":"float","color":"sora","timezone":"Asia/Tokyo"}"#);
+        let client = test_client(vec![graph_def, success()]);
+        let graph_id = GraphId::new("testid").unwrap();
+
+        let graph = client.get_graph(&graph_id).unwrap();
+        assert_eq!(graph.timezone, Some("Asia/Tokyo".to_owned()));
+
+        client.record_pixel(&graph_id, &PixelDate::new("20181018").unwrap(), &Quantity::Int(5)).unwrap();
+        assert_eq!(*client.http_client.uris.borrow(), vec![
+            "https://pixe.la/v1/users/testuser/graphs/testid/graph-def",
+            "https://pixe.la/v1/users/testuser/graphs/testid",
+        ]);
+    }
+
+    #[test]
+    fn get_graph_svg_test() {
+        let client = test_client(vec![HttpResponse::new(200, "<svg></svg>")]);
+        let graph_id = GraphId::new("testid").unwrap();
+
+        let options = SvgOptions::new()
+            .mode(SvgMode::Badge)
+            .appearance(SvgAppearance::Dark)
+            .less_than(Quantity::Float(1.5));
+        assert_eq!(client.get_graph_svg(&graph_id, &options).unwrap(), "<svg></svg>");
+        client.get_graph_svg(&graph_id, &SvgOptions::new()).unwrap();
+        assert_eq!(*client.http_client.uris.borrow(), vec![
+            "https://pixe.la/v1/users/testuser/graphs/testid?mode=badge&appearance=dark&lessThan=1.5",
+            "https://pixe.la/v1/users/testuser/graphs/testid",
+        ]);
+    }
+
+    #[test]
+    fn purge_graph_cache_test() {
+        let client = test_client(vec![success()]);
+        let graph_id = GraphId::new("testid").unwrap();
+
+        let update = GraphUpdate::new().purge_cache_urls(&["http://example.com/graph.svg"]);
+        match client.update_graph(&graph_id, &update) {
+            Err(PixelaClientError::InvalidParameter { rule, .. }) => assert_eq!(rule, PURGE_CACHE_URLS_RULE),
+            _ => panic!("not failed."),
+        };
+
+        client.purge_graph_cache(&graph_id).unwrap();
+        assert_eq!(*client.http_client.uris.borrow(), vec![
+            "https://pixe.la/v1/users/testuser/graphs/testid/purge-cache",
+        ]);
+    }
+
+    #[test]
+    fn get_graph_html_test() {
+        let client = test_client(vec![HttpResponse::new(200, "<html></html>")]);
+        let graph_id = GraphId::new("testid").unwrap();
+
+        client.get_graph_html(&graph_id, GraphHtmlMode::Detail).unwrap();
+        client.get_graph_html(&graph_id, GraphHtmlMode::SimpleShort).unwrap();
+        assert_eq!(*client.http_client.uris.borrow(), vec![
+            "https://pixe.la/v1/users/testuser/graphs/testid.html",
+            "https://pixe.la/v1/users/testuser/graphs/testid.html?mode=simple-short",
+        ]);
+
+        let urls = client.graph_url(&graph_id);
+        assert_eq!(urls.svg, "https://pixe.la/v1/users/testuser/graphs/testid");
+        assert_eq!(urls.html, "https://pixe.la/v1/users/testuser/graphs/testid.html");
+    }
+
+    #[test]
+    fn get_latest_pixel_test() {
+        let latest = HttpResponse::new(200, r#"{"date":"20181018","quantity":"5","optionalData":"{\"note\":\"run\"}"}"#);
+        let not_found = HttpResponse::new(404, r#"{"message":"Specified pixel not found.","isSuccess":false}"#);
+        let today = HttpResponse::new(200, r#"{"quantity":"0"}"#);
+        let client = test_client(vec![latest, not_found, today]);
+        let graph_id = GraphId::new("testid").unwrap();
+
+        let pixel = client.get_latest_pixel(&graph_id).unwrap().unwrap();
+        assert_eq!(pixel.date, PixelDate::new("20181018").unwrap());
+        assert_eq!(pixel.quantity, Quantity::Int(5));
+        assert_eq!(pixel.optional_data, Some(r#"{"note":"run"}"#.to_owned()));
+
+        assert_eq!(client.get_today_pixel(&graph_id, false).unwrap(), None);
+
+        let pixel = client.get_today_pixel(&graph_id, true).unwrap().unwrap();
+        assert_eq!(pixel, TodayPixel { quantity: Quantity::Int(0), optional_data: None });
+
+        // The date of /latest is required.
+        assert!(client.get_latest_pixel(&graph_id).is_err());
+        // Reading does not look up the type of the graph.
+        assert_eq!(*client.http_client.uris.borrow(), vec![
+            "https://pixe.la/v1/users/testuser/graphs/testid/latest",
+            "https://pixe.la/v1/users/testuser/graphs/testid/today",
+            "https://pixe.la/v1/users/testuser/graphs/testid/today?returnEmpty=true",
+            "https://pixe.la/v1/users/testuser/graphs/testid/latest",
+        ]);
+    }
+
+    #[test]
+    fn record_pixels_test() {
+        let bad_request = HttpResponse::new(400, r#"{"message":"Specified quantity is invalid.","isSuccess":false}"#);
+        let client = test_client(vec![graphs(), success(), bad_request]);
+        let graph_id = GraphId::new("testid").unwrap();
+
+        let pixels = vec![Pixel::new(&PixelDate::new("20180101").unwrap(), &Quantity::Int(1)); 365];
+
+        assert!(client.record_pixels(&graph_id, &[]).is_empty());
+        assert_eq!(client.http_client.uris.borrow().len(), 0);
+
+        let results = client.record_pixels(&graph_id, &pixels);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].range, 0..300);
+        assert!(results[0].is_success());
+        assert_eq!(results[1].range, 300..365);
+        assert_eq!(results[1].result.as_ref().unwrap_err().kind(), Some(ErrorKind::BadRequest));
+        assert_eq!(client.http_client.uris.borrow()[1], "https://pixe.la/v1/users/testuser/graphs/testid/pixels");
+    }
+
+    #[test]
+    fn add_test() {
+        let server_error = HttpResponse::new(500, r#"{"message":"Internal server error.","isSuccess":false}"#);
+        let client = test_client(vec![graphs(), server_error, success()]);
+        let graph_id = GraphId::new("testid").unwrap();
+
+        assert!(client.subtract(&graph_id, &Quantity::Float(0.5)).is_err());
+        assert_eq!(client.http_client.uris.borrow().len(), 1);
+
+        let err = client.add(&graph_id, &Quantity::Int(37)).unwrap_err();
+        assert_eq!(err.kind(), Some(ErrorKind::ServerError));
+        assert_eq!(client.http_client.uris.borrow()[1], "https://pixe.la/v1/users/testuser/graphs/testid/add");
+
+        client.add(&graph_id, &Quantity::Int(37)).unwrap();
+        assert_eq!(client.http_client.uris.borrow().len(), 3);
+    }
+
+    #[test]
+    fn profile_test() {
+        let client = test_client(vec![success(), HttpResponse::new(200, "<html></html>")]);
+
+        client.update_profile(&ProfileUpdate::new().display_name("Test User")).unwrap();
+        assert_eq!(client.get_profile_page(&Username::new("otheruser").unwrap()).unwrap(), "<html></html>");
+        assert_eq!(*client.http_client.uris.borrow(), vec![
+            "https://pixe.la/@testuser",
+            "https://pixe.la/@otheruser",
+        ]);
+    }
+
+    #[test]
+    fn notification_test() {
+        let client = test_client(vec![graphs(), success()]);
+        let graph_id = GraphId::new("testid").unwrap();
+        let channel_id = ChannelId::new("testchannel").unwrap();
+
+        let mut rule = NotificationRule::new(&NotificationId::new("testrule").unwrap(), "test", NotificationTarget::Quantity, NotificationCondition::LessThan, Quantity::Float(0.5), &channel_id);
+        match client.create_notification(&graph_id, &rule) {
+            Err(PixelaClientError::InvalidParameter { rule, .. }) => assert_eq!(rule, INT_QUANTITY_RULE),
+            _ => panic!("not failed."),
+        };
+
+        rule.threshold = Quantity::Int(1);
+        client.create_notification(&graph_id, &rule).unwrap();
+        client.delete_notification(&graph_id, &rule.id).unwrap();
+        assert_eq!(*client.http_client.uris.borrow(), vec![
+            "https://pixe.la/v1/users/testuser/graphs",
+            "https://pixe.la/v1/users/testuser/graphs/testid/notifications",
+            "https://pixe.la/v1/users/testuser/graphs/testid/notifications/testrule",
+        ]);
+    }
+
+    #[test]
+    fn webhook_test() {
+        let created = HttpResponse::new(200, r#"{"webhookHash":"0a1b2c","message":"Success.","isSuccess":true}"#);
+        let client = test_client(vec![created, success()]);
+        let graph_id = GraphId::new("testid").unwrap();
+
+        let hash = client.create_webhook(&graph_id, WebhookType::Increment).unwrap();
+        assert_eq!(hash, WebhookHash::new("0a1b2c").unwrap());
+
+        client.invoke_webhook(&hash).unwrap();
+        assert_eq!(*client.http_client.uris.borrow(), vec![
+            "https://pixe.la/v1/users/testuser/webhooks",
+            "https://pixe.la/v1/users/testuser/webhooks/0a1b2c",
+        ]);
+    }
+
+    #[test]
+    fn stopwatch_fallback_test() {
+        let server_error = HttpResponse::new(500, r#"{"message":"Internal server error.","isSuccess":false}"#);
+        let client = test_client(vec![server_error, graphs(), success()]);
+        let graph_id = GraphId::new("testid").unwrap();
+
+        let path = ::std::env::temp_dir().join(format!("pixelast-lib-stopwatch-{}", ::std::process::id()));
+        let local = LocalStopwatch::new(&path);
+
+        client.toggle_stopwatch_or_start_local(&graph_id, &local).unwrap();
+        assert!(local.is_running());
+        assert_eq!(client.http_client.uris.borrow().len(), 1);
+
+        let started = ::std::fs::read_to_string(&path).unwrap();
+        ::std::fs::write(&path, (started.parse::<u64>().unwrap() - 37 * 60).to_string()).unwrap();
+
+        client.stop_local_or_toggle_stopwatch(&graph_id, &local).unwrap();
+        assert!(!local.is_running());
+        assert_eq!(client.http_client.uris.borrow().last().unwrap(), "https://pixe.la/v1/users/testuser/graphs/testid/add");
     }
 }
@@ -0,0 +1,194 @@
+use super::Authentication;
+use super::endpoint::Endpoint;
+use super::response;
+use super::api_call::ApiCall;
+use super::http_client::{RequestContext, HttpResponse, MethodType};
+use super::error::PixelaClientError;
+use super::graph::GraphType;
+use super::types::{GraphId, ChannelId, NotificationId, Quantity};
+
+use serde_json;
+
+/// A notification rule of a graph in Pixela. A notification is sent to the channel when the condition is met.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NotificationRule {
+    /// It is an ID for identifying the notification rule.
+    pub id: NotificationId,
+    /// It is the name of the notification rule.
+    pub name: String,
+    /// What the condition is evaluated against.
+    pub target: NotificationTarget,
+    /// How the target is compared with the threshold.
+    pub condition: NotificationCondition,
+    /// The value compared with the target. It has the type of the graph.
+    pub threshold: Quantity,
+    /// The channel to which the notification is sent.
+    #[serde(rename = "channelID")]
+    pub channel_id: ChannelId,
+}
+
+impl NotificationRule {
+    /// Constructs a new `NotificationRule`.
+    pub fn new(id: &NotificationId, name: &str, target: NotificationTarget, condition: NotificationCondition, threshold: Quantity, channel_id: &ChannelId) -> Self {
+        Self {
+            id: id.to_owned(),
+            name: name.to_owned(),
+            target,
+            condition,
+            threshold,
+            channel_id: channel_id.to_owned(),
+        }
+    }
+}
+
+/// Targets of a notification rule.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationTarget {
+    /// The quantity of the pixel of the day.
+    #[serde(rename = "quantity")]
+    Quantity,
+}
+
+/// Conditions of a notification rule.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationCondition {
+    #[serde(rename = ">")]
+    GreaterThan,
+    #[serde(rename = "=")]
+    Equal,
+    #[serde(rename = "<")]
+    LessThan,
+    #[serde(rename = "multipleOf")]
+    MultipleOf,
+}
+
+#[derive(Serialize, Debug)]
+struct NotificationUpdate<'a> {
+    name: &'a str,
+    target: NotificationTarget,
+    condition: NotificationCondition,
+    threshold: Quantity,
+    #[serde(rename = "channelID")]
+    channel_id: &'a ChannelId,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct NotificationRules {
+    notifications: Vec<NotificationRule>,
+}
+
+pub(crate) fn create(endpoint: &Endpoint, auth: &Authentication, graph_id: &GraphId, rule: &NotificationRule, graph_type: Option<GraphType>) -> Result<ApiCall<()>, PixelaClientError> {
+    let param = NotificationRule {
+        threshold: rule.threshold.for_graph_type(graph_type)?,
+        ..rule.to_owned()
+    };
+
+    let uri = endpoint.notifications(auth.username.as_str(), graph_id.as_str());
+    let body = serde_json::to_string(&param)?;
+    let context = RequestContext::new(
+        &uri,
+        MethodType::Post,
+        Some(&body),
+        Some(auth.token.as_str()),
+    );
+
+    Ok(ApiCall::new(context, response::build_result).non_idempotent())
+}
+
+pub(crate) fn get_all(endpoint: &Endpoint, auth: &Authentication, graph_id: &GraphId) -> Result<ApiCall<Vec<NotificationRule>>, PixelaClientError> {
+    let uri = endpoint.notifications(auth.username.as_str(), graph_id.as_str());
+    let context = RequestContext::new(
+        &uri,
+        MethodType::Get,
+        None,
+        Some(auth.token.as_str()),
+    );
+
+    Ok(ApiCall::new(context, parse_notification_rules))
+}
+
+fn parse_notification_rules(response: &HttpResponse) -> Result<Vec<NotificationRule>, PixelaClientError> {
+    response::check_error(response)?;
+
+    let res: NotificationRules = serde_json::from_str(&response.body)?;
+    Ok(res.notifications)
+}
+
+/// Replaces the notification rule identified by its id.
+pub(crate) fn update(endpoint: &Endpoint, auth: &Authentication, graph_id: &GraphId, rule: &NotificationRule, graph_type: Option<GraphType>) -> Result<ApiCall<()>, PixelaClientError> {
+    let param = NotificationUpdate {
+        name: &rule.name,
+        target: rule.target,
+        condition: rule.condition,
+        threshold: rule.threshold.for_graph_type(graph_type)?,
+        channel_id: &rule.channel_id,
+    };
+
+    let uri = endpoint.notification(auth.username.as_str(), graph_id.as_str(), rule.id.as_str());
+    let body = serde_json::to_string(&param)?;
+    let context = RequestContext::new(
+        &uri,
+        MethodType::Put,
+        Some(&body),
+        Some(auth.token.as_str()),
+    );
+
+    Ok(ApiCall::new(context, response::build_result))
+}
+
+pub(crate) fn delete(endpoint: &Endpoint, auth: &Authentication, graph_id: &GraphId, notification_id: &NotificationId) -> Result<ApiCall<()>, PixelaClientError> {
+    let uri = endpoint.notification(auth.username.as_str(), graph_id.as_str(), notification_id.as_str());
+    let context = RequestContext::new(
+        &uri,
+        MethodType::Delete,
+        None,
+        Some(auth.token.as_str()),
+    );
+
+    Ok(ApiCall::new(context, response::build_result).non_idempotent())
+}
+
+#[cfg(test)]
+mod notification_test {
+    use super::*;
+
+    fn test_rule() -> NotificationRule {
+        NotificationRule::new(
+            &NotificationId::new("testrule").unwrap(),
+            "test",
+            NotificationTarget::Quantity,
+            NotificationCondition::LessThan,
+            Quantity::Int(5),
+            &ChannelId::new("testchannel").unwrap(),
+        )
+    }
+
+    #[test]
+    fn notification_rule_serialize_test() {
+        let json = serde_json::to_string(&test_rule()).unwrap();
+        assert_eq!(json, r#"{"id":"testrule","name":"test","target":"quantity","condition":"<","threshold":"5","channelID":"testchannel"}"#);
+    }
+
+    #[test]
+    fn notification_rules_deserialize_test() {
+        let json = r#"{"notifications":[{"id":"testrule","name":"test","target":"quantity","condition":"<","threshold":"5","channelID":"testchannel"}]}"#;
+
+        let res = parse_notification_rules(&HttpResponse::new(200, json)).unwrap();
+        assert_eq!(res, vec![test_rule()]);
+    }
+
+    #[test]
+    fn notification_update_serialize_test() {
+        let rule = test_rule();
+        let param = NotificationUpdate {
+            name: &rule.name,
+            target: rule.target,
+            condition: NotificationCondition::MultipleOf,
+            threshold: rule.threshold.for_graph_type(Some(GraphType::Float)).unwrap(),
+            channel_id: &rule.channel_id,
+        };
+
+        let json = serde_json::to_string(&param).unwrap();
+        assert_eq!(json, r#"{"name":"test","target":"quantity","condition":"multipleOf","threshold":"5.0","channelID":"testchannel"}"#);
+    }
+}
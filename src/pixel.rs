@@ -1,130 +1,430 @@
 use super::Authentication;
-use super::endpoint;
+use super::endpoint::Endpoint;
 use super::response;
-use super::response::{ApiRequestResult};
-use super::http_client::{HttpClient, RequestContext, MethodType, HeaderType};
-use super::error::PixelaClientError;
+use super::api_call::ApiCall;
+use super::http_client::{RequestContext, HttpResponse, MethodType, HeaderType};
+use super::error::{PixelaClientError, ErrorKind};
+use super::validation;
+use super::graph::GraphType;
+use super::types::{GraphId, PixelDate, Quantity};
 
-use std::collections::HashMap;
-use failure::Error;
+use std::ops::Range;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
 use serde_json;
-use serde_json::{Number};
 
 /// Data representing the quantity of each day.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Pixel {
     /// The date on which the quantity is to be recorded. It is specified in yyyyMMdd format.
-    pub date: String,
+    pub date: PixelDate,
     /// Specify the quantity to be registered on the specified date.
-    /// Validation rule: int^\-?[0-9]+ float^\-?[0-9]+\.[0-9]+
-    pub quantity: String,
+    pub quantity: Quantity,
+    /// Additional information for the pixel as a JSON string, up to 10KB.
+    #[serde(rename = "optionalData", default, skip_serializing_if = "Option::is_none")]
+    pub optional_data: Option<String>,
+}
+
+impl Pixel {
+    /// Constructs a new `Pixel` without optional data.
+    pub fn new(date: &PixelDate, quantity: &Quantity) -> Self {
+        Self {
+            date: date.to_owned(),
+            quantity: quantity.to_owned(),
+            optional_data: None,
+        }
+    }
+
+    /// Constructs a new `Pixel` with the optional data serialized into JSON.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when the data can not be serialized or its JSON is larger than 10KB.
+    pub fn with_optional_data<D>(date: &PixelDate, quantity: &Quantity, data: &D) -> Result<Self, PixelaClientError> where D: Serialize {
+        let optional_data = serde_json::to_string(data)?;
+        validation::validate_optional_data(&optional_data)?;
+
+        Ok(Self {
+            optional_data: Some(optional_data),
+            ..Pixel::new(date, quantity)
+        })
+    }
+
+    /// Returns the optional data deserialized into `D`, or `None` when the pixel has no optional data.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when the optional data can not be deserialized into `D`.
+    pub fn optional_data<D>(&self) -> Result<Option<D>, PixelaClientError> where D: DeserializeOwned {
+        match self.optional_data {
+            Some(ref v) => Ok(Some(serde_json::from_str(v)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// The pixel of today returned by `get_today_pixel`.
+///
+/// Pixela decides the day in the timezone of the graph and does not return its date.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TodayPixel {
+    /// The quantity recorded today.
+    pub quantity: Quantity,
+    /// Additional information for the pixel as a JSON string.
+    #[serde(rename = "optionalData", default, skip_serializing_if = "Option::is_none")]
+    pub optional_data: Option<String>,
+}
+
+/// Pixels of a graph returned by `list_pixels`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum PixelList {
+    /// The dates of the recorded pixels, returned without `with_body`.
+    Dates(Vec<PixelDate>),
+    /// The recorded pixels, returned with `with_body`.
+    Pixels(Vec<Pixel>),
+}
+
+impl PixelList {
+    /// Returns the dates of the pixels.
+    pub fn dates(&self) -> Vec<&PixelDate> {
+        match self {
+            PixelList::Dates(v) => v.iter().collect(),
+            PixelList::Pixels(v) => v.iter().map(|p| &p.date).collect(),
+        }
+    }
+
+    /// Returns the number of the pixels.
+    pub fn len(&self) -> usize {
+        match self {
+            PixelList::Dates(v) => v.len(),
+            PixelList::Pixels(v) => v.len(),
+        }
+    }
+
+    /// Returns `true` when no pixel is recorded in the range.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// The maximum number of pixels Pixela registers in one batch request.
+pub const MAX_PIXELS_PER_BATCH: usize = 300;
+
+/// The result of a chunk of pixels sent by `record_pixels`.
+#[derive(Debug)]
+pub struct PixelBatchResult {
+    /// The range of the chunk in the pixels given to `record_pixels`.
+    pub range: Range<usize>,
+    /// Whether Pixela registered the chunk.
+    pub result: Result<(), PixelaClientError>,
+}
+
+impl PixelBatchResult {
+    /// Returns `true` when Pixela registered the chunk.
+    pub fn is_success(&self) -> bool {
+        self.result.is_ok()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct PixelDates {
+    pixels: Vec<PixelDate>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct PixelBodies {
+    pixels: Vec<Pixel>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct PixelQuantity {
-    pub quantity: Number,
+    pub quantity: Quantity,
+    #[serde(rename = "optionalData", default, skip_serializing_if = "Option::is_none")]
+    pub optional_data: Option<String>,
 }
 
-pub(crate) fn create<T>(auth: &Authentication, graph_id: &str, pixel: &Pixel) -> Result<(), Error> where T: HttpClient {
-    let body = serde_json::to_string(pixel)?;
-    let uri = endpoint::graph(&auth.username, graph_id);
+pub(crate) fn create(endpoint: &Endpoint, auth: &Authentication, graph_id: &GraphId, pixel: &Pixel, graph_type: Option<GraphType>) -> Result<ApiCall<()>, PixelaClientError> {
+    if let Some(ref v) = pixel.optional_data {
+        validation::validate_optional_data(v)?;
+    }
+
+    let param = Pixel {
+        date: pixel.date.to_owned(),
+        quantity: pixel.quantity.for_graph_type(graph_type)?,
+        optional_data: pixel.optional_data.to_owned(),
+    };
+
+    let body = serde_json::to_string(&param)?;
+    let uri = endpoint.graph(auth.username.as_str(), graph_id.as_str());
     let context = RequestContext::new(
         &uri,
         MethodType::Post,
         Some(&body),
-        Some(&auth.token),
+        Some(auth.token.as_str()),
     );
 
-    let body = T::do_request(&context)?;
-    response::build_result(&body)
+    Ok(ApiCall::new(context, response::build_result))
 }
 
-pub(crate) fn update<T>(auth: &Authentication, graph_id: &str, pixel: &Pixel) -> Result<(), Error> where T: HttpClient {
-    let mut hash: HashMap<&str, &str> = HashMap::new();
-    hash.insert("quantity", &pixel.quantity);
+/// Splits the pixels into the ranges of the chunks sent by `create_batch`.
+pub(crate) fn batch_ranges(len: usize) -> Vec<Range<usize>> {
+    (0..len).step_by(MAX_PIXELS_PER_BATCH)
+        .map(|start| start..::std::cmp::min(start + MAX_PIXELS_PER_BATCH, len))
+        .collect()
+}
+
+pub(crate) fn create_batch(endpoint: &Endpoint, auth: &Authentication, graph_id: &GraphId, pixels: &[Pixel], graph_type: Option<GraphType>) -> Result<ApiCall<()>, PixelaClientError> {
+    let mut params = Vec::with_capacity(pixels.len());
+    for pixel in pixels {
+        if let Some(ref v) = pixel.optional_data {
+            validation::validate_optional_data(v)?;
+        }
 
-    let body = serde_json::to_string(&hash)?;
-    let uri = endpoint::pixel(&auth.username, graph_id, &pixel.date);
+        params.push(Pixel {
+            quantity: pixel.quantity.for_graph_type(graph_type)?,
+            ..pixel.to_owned()
+        });
+    }
+
+    let body = serde_json::to_string(&params)?;
+    let uri = endpoint.pixels(auth.username.as_str(), graph_id.as_str());
+    let context = RequestContext::new(
+        &uri,
+        MethodType::Post,
+        Some(&body),
+        Some(auth.token.as_str()),
+    );
+
+    Ok(ApiCall::new(context, response::build_result))
+}
+
+pub(crate) fn update(endpoint: &Endpoint, auth: &Authentication, graph_id: &GraphId, pixel: &Pixel, graph_type: Option<GraphType>) -> Result<ApiCall<()>, PixelaClientError> {
+    if let Some(ref v) = pixel.optional_data {
+        validation::validate_optional_data(v)?;
+    }
+
+    let param = PixelQuantity {
+        quantity: pixel.quantity.for_graph_type(graph_type)?,
+        optional_data: pixel.optional_data.to_owned(),
+    };
+
+    let body = serde_json::to_string(&param)?;
+    let uri = endpoint.pixel(auth.username.as_str(), graph_id.as_str(), pixel.date.as_str());
     let context = RequestContext::new(
         &uri,
         MethodType::Put,
         Some(&body),
-        Some(&auth.token),
+        Some(auth.token.as_str()),
     );
 
-    let body = T::do_request(&context)?;
-    response::build_result(&body)
+    Ok(ApiCall::new(context, response::build_result))
 }
 
-pub(crate) fn delete<T>(auth: &Authentication, graph_id: &str, date: &str) -> Result<(), Error> where T: HttpClient {
-    let uri = endpoint::pixel(&auth.username, graph_id, date);
+pub(crate) fn delete(endpoint: &Endpoint, auth: &Authentication, graph_id: &GraphId, date: &PixelDate) -> Result<ApiCall<()>, PixelaClientError> {
+    let uri = endpoint.pixel(auth.username.as_str(), graph_id.as_str(), date.as_str());
     let context = RequestContext::new(
         &uri,
         MethodType::Delete,
         None,
-        Some(&auth.token),
+        Some(auth.token.as_str()),
     );
 
-    let body = T::do_request(&context)?;
-    response::build_result(&body)
+    Ok(ApiCall::new(context, response::build_result).non_idempotent())
 }
 
-pub(crate) fn get<T>(auth: &Authentication, graph_id: &str, date: &str) -> Result<Pixel, Error> where T: HttpClient {
-    let uri = endpoint::pixel(&auth.username, graph_id, date);
+pub(crate) fn get(endpoint: &Endpoint, auth: &Authentication, graph_id: &GraphId, date: &PixelDate, graph_type: Option<GraphType>) -> Result<ApiCall<Pixel>, PixelaClientError> {
+    let uri = endpoint.pixel(auth.username.as_str(), graph_id.as_str(), date.as_str());
     let context = RequestContext::new(
         &uri,
         MethodType::Get,
         None,
-        Some(&auth.token),
+        Some(auth.token.as_str()),
     );
 
-    let body = T::do_request(&context)?;
+    let date = date.to_owned();
+    Ok(ApiCall::new(context, move |res| parse_pixel(res, date.clone(), graph_type)))
+}
 
-    let res: Result<ApiRequestResult, _> = serde_json::from_str(&body);
-    if let Ok(v) = res {
-        return Err(PixelaClientError::RequestNotSuccess(v.message).into());
-    }
+fn parse_pixel(response: &HttpResponse, date: PixelDate, graph_type: Option<GraphType>) -> Result<Pixel, PixelaClientError> {
+    response::check_error(response)?;
 
-    let res: PixelQuantity = serde_json::from_str(&body)?;
+    let res: PixelQuantity = serde_json::from_str(&response.body)?;
     Ok(Pixel {
-        date: date.to_owned(),
-        quantity: res.quantity.to_string(),
+        date,
+        quantity: received_quantity(res.quantity, graph_type),
+        optional_data: res.optional_data,
     })
 }
 
-pub(crate) fn increment<T>(auth: &Authentication, graph_id: &str) -> Result<(), Error> where T: HttpClient {
-    let uri = endpoint::increment(&auth.username, graph_id);
+pub(crate) fn get_latest(endpoint: &Endpoint, auth: &Authentication, graph_id: &GraphId, graph_type: Option<GraphType>) -> Result<ApiCall<Option<Pixel>>, PixelaClientError> {
+    let uri = endpoint.latest(auth.username.as_str(), graph_id.as_str());
+    let context = RequestContext::new(
+        &uri,
+        MethodType::Get,
+        None,
+        Some(auth.token.as_str()),
+    );
+
+    Ok(ApiCall::new(context, move |res| {
+        let pixel = parse_found_pixel::<Pixel>(res)?;
+        Ok(pixel.map(|v| Pixel { quantity: received_quantity(v.quantity, graph_type), ..v }))
+    }))
+}
+
+pub(crate) fn get_today(endpoint: &Endpoint, auth: &Authentication, graph_id: &GraphId, return_empty: bool, graph_type: Option<GraphType>) -> Result<ApiCall<Option<TodayPixel>>, PixelaClientError> {
+    let uri = endpoint.today(auth.username.as_str(), graph_id.as_str());
+    let mut context = RequestContext::new(
+        &uri,
+        MethodType::Get,
+        None,
+        Some(auth.token.as_str()),
+    );
+
+    if return_empty {
+        context.insert_query("returnEmpty", "true");
+    }
+
+    Ok(ApiCall::new(context, move |res| {
+        let pixel = parse_found_pixel::<TodayPixel>(res)?;
+        Ok(pixel.map(|v| TodayPixel { quantity: received_quantity(v.quantity, graph_type), ..v }))
+    }))
+}
+
+/// A missing pixel is `None`.
+fn parse_found_pixel<T>(response: &HttpResponse) -> Result<Option<T>, PixelaClientError> where T: DeserializeOwned {
+    if let Err(e) = response::check_error(response) {
+        return match e.kind() {
+            Some(ErrorKind::PixelNotFound) => Ok(None),
+            _ => Err(e),
+        };
+    }
+
+    Ok(Some(serde_json::from_str(&response.body)?))
+}
+
+pub(crate) fn list(endpoint: &Endpoint, auth: &Authentication, graph_id: &GraphId, from: Option<&PixelDate>, to: Option<&PixelDate>, with_body: bool, graph_type: Option<GraphType>) -> Result<ApiCall<PixelList>, PixelaClientError> {
+    let uri = endpoint.pixels(auth.username.as_str(), graph_id.as_str());
     let mut context = RequestContext::new(
         &uri,
+        MethodType::Get,
+        None,
+        Some(auth.token.as_str()),
+    );
+
+    if let Some(v) = from {
+        context.insert_query("from", v.as_str());
+    }
+    if let Some(v) = to {
+        context.insert_query("to", v.as_str());
+    }
+    if with_body {
+        context.insert_query("withBody", "true");
+    }
+
+    Ok(ApiCall::new(context, move |res| parse_pixel_list(res, with_body, graph_type)))
+}
+
+fn parse_pixel_list(response: &HttpResponse, with_body: bool, graph_type: Option<GraphType>) -> Result<PixelList, PixelaClientError> {
+    response::check_error(response)?;
+
+    if !with_body {
+        let res: PixelDates = serde_json::from_str(&response.body)?;
+        return Ok(PixelList::Dates(res.pixels));
+    }
+
+    let res: PixelBodies = serde_json::from_str(&response.body)?;
+    let pixels = res.pixels.into_iter()
+        .map(|v| Pixel {
+            quantity: received_quantity(v.quantity, graph_type),
+            ..v
+        })
+        .collect();
+
+    Ok(PixelList::Pixels(pixels))
+}
+
+/// Pixela may return the quantity of a `float` graph without a fraction.
+pub(crate) fn received_quantity(quantity: Quantity, graph_type: Option<GraphType>) -> Quantity {
+    match (quantity, graph_type) {
+        (Quantity::Int(v), Some(GraphType::Float)) => Quantity::Float(v as f64),
+        (v, _) => v,
+    }
+}
+
+pub(crate) fn add(endpoint: &Endpoint, auth: &Authentication, graph_id: &GraphId, quantity: &Quantity, graph_type: Option<GraphType>) -> Result<ApiCall<()>, PixelaClientError> {
+    let uri = endpoint.add(auth.username.as_str(), graph_id.as_str());
+    change_quantity(&uri, auth, quantity, graph_type)
+}
+
+pub(crate) fn subtract(endpoint: &Endpoint, auth: &Authentication, graph_id: &GraphId, quantity: &Quantity, graph_type: Option<GraphType>) -> Result<ApiCall<()>, PixelaClientError> {
+    let uri = endpoint.subtract(auth.username.as_str(), graph_id.as_str());
+    change_quantity(&uri, auth, quantity, graph_type)
+}
+
+fn change_quantity(uri: &str, auth: &Authentication, quantity: &Quantity, graph_type: Option<GraphType>) -> Result<ApiCall<()>, PixelaClientError> {
+    let param = PixelQuantity {
+        quantity: quantity.for_graph_type(graph_type)?,
+        optional_data: None,
+    };
+
+    let body = serde_json::to_string(&param)?;
+    let context = RequestContext::new(
+        uri,
         MethodType::Put,
+        Some(&body),
+        Some(auth.token.as_str()),
+    );
+
+    Ok(ApiCall::new(context, response::build_result).non_idempotent())
+}
+
+pub(crate) fn stopwatch(endpoint: &Endpoint, auth: &Authentication, graph_id: &GraphId) -> Result<ApiCall<()>, PixelaClientError> {
+    let uri = endpoint.stopwatch(auth.username.as_str(), graph_id.as_str());
+    let mut context = RequestContext::new(
+        &uri,
+        MethodType::Post,
         None,
-        Some(&auth.token),
+        Some(auth.token.as_str()),
     );
 
     context.insert_header(HeaderType::ContentLength, "0");
 
-    let body = T::do_request(&context)?;
-    response::build_result(&body)
+    Ok(ApiCall::new(context, response::build_result).non_idempotent())
 }
 
-pub(crate) fn decrement<T>(auth: &Authentication, graph_id: &str) -> Result<(), Error> where T: HttpClient {
-    let uri = endpoint::decrement(&auth.username, graph_id);
+pub(crate) fn increment(endpoint: &Endpoint, auth: &Authentication, graph_id: &GraphId) -> Result<ApiCall<()>, PixelaClientError> {
+    let uri = endpoint.increment(auth.username.as_str(), graph_id.as_str());
     let mut context = RequestContext::new(
         &uri,
         MethodType::Put,
         None,
-        Some(&auth.token),
+        Some(auth.token.as_str()),
     );
 
     context.insert_header(HeaderType::ContentLength, "0");
 
-    let body = T::do_request(&context)?;
-    response::build_result(&body)
+    Ok(ApiCall::new(context, response::build_result).non_idempotent())
+}
+
+pub(crate) fn decrement(endpoint: &Endpoint, auth: &Authentication, graph_id: &GraphId) -> Result<ApiCall<()>, PixelaClientError> {
+    let uri = endpoint.decrement(auth.username.as_str(), graph_id.as_str());
+    let mut context = RequestContext::new(
+        &uri,
+        MethodType::Put,
+        None,
+        Some(auth.token.as_str()),
+    );
+
+    context.insert_header(HeaderType::ContentLength, "0");
+
+    Ok(ApiCall::new(context, response::build_result).non_idempotent())
 }
 
 #[cfg(test)]
 mod pixel_test {
     use super::*;
+    use std::collections::HashMap;
 
     #[test]
     fn pixel_quantity_deserialize_test() {
@@ -135,4 +435,58 @@ mod pixel_test {
             panic!("failed serialize params");
         };
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn parse_pixel_test() {
+        let date = PixelDate::new("20181018").unwrap();
+        let response = HttpResponse::new(200, r#"{"quantity":"5"}"#);
+
+        let pixel = parse_pixel(&response, date.clone(), None).unwrap();
+        assert_eq!(pixel.quantity, Quantity::Int(5));
+
+        let pixel = parse_pixel(&response, date, Some(GraphType::Float)).unwrap();
+        assert_eq!(pixel.quantity, Quantity::Float(5.0));
+    }
+
+    #[test]
+    fn optional_data_test() {
+        let date = PixelDate::new("20181018").unwrap();
+        let response = HttpResponse::new(200, r#"{"quantity":"5","optionalData":"{\"key\":\"value\"}"}"#);
+
+        let pixel = parse_pixel(&response, date.clone(), None).unwrap();
+        let data: HashMap<String, String> = pixel.optional_data().unwrap().unwrap();
+        assert_eq!(data["key"], "value");
+
+        let pixel = Pixel::with_optional_data(&date, &Quantity::Int(5), &data).unwrap();
+        assert_eq!(serde_json::to_string(&pixel).unwrap(), r#"{"date":"20181018","quantity":"5","optionalData":"{\"key\":\"value\"}"}"#);
+        assert!(Pixel::with_optional_data(&date, &Quantity::Int(5), &"a".repeat(10240)).is_err());
+        assert_eq!(serde_json::to_string(&Pixel::new(&date, &Quantity::Int(5))).unwrap(), r#"{"date":"20181018","quantity":"5"}"#);
+    }
+
+    #[test]
+    fn parse_pixel_list_test() {
+        let response = HttpResponse::new(200, r#"{"pixels":["20180101","20180102"]}"#);
+        let list = parse_pixel_list(&response, false, None).unwrap();
+        assert_eq!(list, PixelList::Dates(vec![PixelDate::new("20180101").unwrap(), PixelDate::new("20180102").unwrap()]));
+
+        let response = HttpResponse::new(200, r#"{"pixels":[{"date":"20180101","quantity":"5"},{"date":"20180102","quantity":"1.5","optionalData":"{}"}]}"#);
+        let list = parse_pixel_list(&response, true, Some(GraphType::Float)).unwrap();
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.dates()[1].as_str(), "20180102");
+        match list {
+            PixelList::Pixels(v) => {
+                assert_eq!(v[0].quantity, Quantity::Float(5.0));
+                assert_eq!(v[1].optional_data, Some("{}".to_owned()));
+            },
+            _ => panic!("not pixels."),
+        };
+    }
+
+    #[test]
+    fn batch_ranges_test() {
+        assert!(batch_ranges(0).is_empty());
+        assert_eq!(batch_ranges(300), vec![0..300]);
+        assert_eq!(batch_ranges(601), vec![0..300, 300..600, 600..601]);
+    }
+
+}
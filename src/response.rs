@@ -1,32 +1,62 @@
 use super::PixelaClientError;
+use super::http_client::HttpResponse;
 
 use serde_json;
-use failure::Error;
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ApiRequestResult {
     pub message: String,
     pub is_success: bool,
+    #[serde(default)]
+    pub is_rejected: bool,
 }
 
-pub fn build_result(json: &str) -> Result<(), Error> {
-    let res: ApiRequestResult = serde_json::from_str(&json)?;
+pub fn build_result(response: &HttpResponse) -> Result<(), PixelaClientError> {
+    let res: ApiRequestResult = match serde_json::from_str(&response.body) {
+        Ok(v) => v,
+        Err(_) if is_error_status(response) => return Err(status_error(response)),
+        Err(e) => return Err(e.into()),
+    };
 
     if !res.is_success {
-        return Err(PixelaClientError::RequestNotSuccess(res.message).into());
+        return Err(PixelaClientError::request_not_success(response.status, res.message, res.is_rejected));
     }
 
     Ok(())
 }
 
+/// Fails when the response of a GET request is an error message instead of the requested content.
+pub fn check_error(response: &HttpResponse) -> Result<(), PixelaClientError> {
+    let res: Result<ApiRequestResult, _> = serde_json::from_str(&response.body);
+    if let Ok(v) = res {
+        return Err(PixelaClientError::request_not_success(response.status, v.message, v.is_rejected));
+    }
+
+    if is_error_status(response) {
+        return Err(status_error(response));
+    }
+
+    Ok(())
+}
+
+fn is_error_status(response: &HttpResponse) -> bool {
+    response.status >= 400
+}
+
+/// A failure answered by something other than Pixela, such as a proxy, is classified by its status alone.
+fn status_error(response: &HttpResponse) -> PixelaClientError {
+    PixelaClientError::request_not_success(response.status, response.body.to_owned(), false)
+}
+
 #[cfg(test)]
 mod response_test {
     use super::*;
+    use super::super::error::ErrorKind;
 
     #[test]
     fn build_result_is_success_test() {
-        let body = r#"{"message":"success message","isSuccess":true}"#;
+        let body = HttpResponse::new(200, r#"{"message":"success message","isSuccess":true}"#);
         let res = build_result(&body);
 
         if let Err(e) = res {
@@ -36,21 +66,63 @@ mod response_test {
 
     #[test]
     fn build_result_is_not_success_test() {
-        let body = r#"{"message":"failed message","isSuccess":false}"#;
+        let body = HttpResponse::new(404, r#"{"message":"Specified graph not found.","isSuccess":false}"#);
         let res = build_result(&body);
 
-        if let Ok(_) = res {
-            panic!("not failed.");
+        match res {
+            Err(PixelaClientError::RequestNotSuccess { status, message, kind }) => {
+                assert_eq!(status, 404);
+                assert_eq!(message, "Specified graph not found.");
+                assert_eq!(kind, ErrorKind::GraphNotFound);
+            },
+            _ => panic!("not failed."),
+        };
+    }
+
+    #[test]
+    fn build_result_is_rejected_test() {
+        let body = HttpResponse::new(503, r#"{"message":"Please retry this request.","isSuccess":false,"isRejected":true}"#);
+        let res = build_result(&body);
+
+        match res {
+            Err(e) => assert_eq!(e.kind(), Some(ErrorKind::Rejected)),
+            Ok(_) => panic!("not failed."),
         };
     }
 
     #[test]
     fn build_result_is_not_json_body_test() {
-        let body = "not json body";
+        let body = HttpResponse::new(200, "not json body");
         let res = build_result(&body);
 
-        if let Ok(_) = res {
+        if res.is_ok() {
             panic!("not failed.");
         };
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn build_result_is_not_json_error_status_test() {
+        let body = HttpResponse::new(502, "<html>Bad Gateway</html>");
+
+        match build_result(&body) {
+            Err(PixelaClientError::RequestNotSuccess { status, kind, .. }) => {
+                assert_eq!(status, 502);
+                assert_eq!(kind, ErrorKind::ServerError);
+            },
+            _ => panic!("not failed."),
+        };
+    }
+
+    #[test]
+    fn check_error_test() {
+        assert!(check_error(&HttpResponse::new(200, "<svg></svg>")).is_ok());
+
+        match check_error(&HttpResponse::new(503, "<html>Service Unavailable</html>")) {
+            Err(e) => {
+                assert_eq!(e.status(), Some(503));
+                assert_eq!(e.kind(), Some(ErrorKind::ServerError));
+            },
+            Ok(_) => panic!("not failed."),
+        };
+    }
+}
@@ -0,0 +1,136 @@
+use super::error::{PixelaClientError, ErrorKind};
+
+use rand::{self, Rng};
+use std::cmp;
+use std::time::Duration;
+
+/// Policy to retry requests which Pixela rejected or failed to process.
+///
+/// Requests rejected at random for non-supporter users are always retried, because Pixela did not process them.
+/// Server errors and network failures are retried only for idempotent requests,
+/// so that `increment`, `decrement`, `add`, `subtract`, the stopwatch and the creation of users and graphs are never applied twice.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let policy = RetryPolicy::new(5)
+///     .base_delay(Duration::from_millis(200))
+///     .max_delay(Duration::from_secs(5));
+///
+/// let client = PixelaClient::builder("username", "usertoken")
+///     .retry_policy(policy)
+///     .build();
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: bool,
+}
+
+impl RetryPolicy {
+    /// Constructs a new `RetryPolicy` which sends a request at most `max_attempts` times.
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts: cmp::max(max_attempts, 1),
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(2),
+            jitter: true,
+        }
+    }
+
+    /// Constructs a `RetryPolicy` which never retries.
+    pub fn none() -> Self {
+        RetryPolicy::new(1)
+    }
+
+    /// Sets the delay before the first retry. It doubles on every retry. Default is 100 milliseconds.
+    pub fn base_delay(mut self, delay: Duration) -> Self {
+        self.base_delay = delay;
+        self
+    }
+
+    /// Sets the upper bound of the delay between retries. Default is 2 seconds.
+    pub fn max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    /// Sets whether the delay is randomized between zero and its exponential value. Default is `true`.
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Returns the maximum number of times a request is sent.
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    pub(crate) fn should_retry(&self, attempt: u32, err: &PixelaClientError, idempotent: bool) -> bool {
+        if attempt >= self.max_attempts {
+            return false;
+        }
+
+        match err {
+            PixelaClientError::RequestNotSuccess { kind: ErrorKind::Rejected, .. } => true,
+            PixelaClientError::RequestNotSuccess { kind: ErrorKind::ServerError, .. } => idempotent,
+            PixelaClientError::HttpClientError(_) => idempotent,
+            _ => false,
+        }
+    }
+
+    /// Returns the delay before sending the request again after the given attempt.
+    pub(crate) fn delay(&self, attempt: u32) -> Duration {
+        let exponent = cmp::min(attempt.saturating_sub(1), 16);
+        let delay = cmp::min(self.base_delay * (1 << exponent), self.max_delay);
+
+        if !self.jitter || delay == Duration::from_millis(0) {
+            return delay;
+        }
+
+        let millis = delay.as_secs() * 1000 + u64::from(delay.subsec_millis());
+        Duration::from_millis(rand::thread_rng().gen_range(0, millis + 1))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy::new(3)
+    }
+}
+
+#[cfg(test)]
+mod retry_test {
+    use super::*;
+
+    #[test]
+    fn should_retry_test() {
+        let policy = RetryPolicy::new(3);
+        let rejected = PixelaClientError::request_not_success(503, "Please retry this request.".to_owned(), true);
+        let server_error = PixelaClientError::request_not_success(500, "Internal server error.".to_owned(), false);
+        let not_found = PixelaClientError::request_not_success(404, "Specified graph not found.".to_owned(), false);
+
+        assert!(policy.should_retry(1, &rejected, false));
+        assert!(policy.should_retry(1, &server_error, true));
+        assert!(!policy.should_retry(1, &server_error, false));
+        assert!(!policy.should_retry(1, &not_found, true));
+        assert!(!policy.should_retry(3, &rejected, true));
+    }
+
+    #[test]
+    fn delay_test() {
+        let policy = RetryPolicy::new(10)
+            .base_delay(Duration::from_millis(100))
+            .max_delay(Duration::from_millis(300))
+            .jitter(false);
+
+        assert_eq!(policy.delay(1), Duration::from_millis(100));
+        assert_eq!(policy.delay(2), Duration::from_millis(200));
+        assert_eq!(policy.delay(3), Duration::from_millis(300));
+
+        let policy = policy.jitter(true);
+        assert!(policy.delay(5) <= Duration::from_millis(300));
+    }
+}
@@ -0,0 +1,121 @@
+use super::error::PixelaClientError;
+
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A stopwatch measured on this machine, used when the stopwatch of Pixela is unavailable.
+///
+/// The start time is persisted to a file, so that the measurement survives restarts of the process.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let stopwatch = LocalStopwatch::new("/var/tmp/pixela-graphid.stopwatch");
+/// client.toggle_stopwatch_or_start_local(&graph_id, &stopwatch)?;
+/// // ...
+/// client.stop_local_or_toggle_stopwatch(&graph_id, &stopwatch)?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct LocalStopwatch {
+    path: PathBuf,
+}
+
+impl LocalStopwatch {
+    /// Constructs a new `LocalStopwatch` which persists its start time to the file at `path`.
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Returns `true` when the stopwatch has been started and not stopped yet.
+    pub fn is_running(&self) -> bool {
+        self.path.exists()
+    }
+
+    /// Starts the measurement. The start time is kept when the stopwatch is already running.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when the file can not be written.
+    pub fn start(&self) -> Result<(), PixelaClientError> {
+        if self.is_running() {
+            return Ok(());
+        }
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        fs::write(&self.path, now.as_secs().to_string())?;
+
+        Ok(())
+    }
+
+    /// Returns the minutes elapsed since the start, or `None` when the stopwatch is not running.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when the file can not be read.
+    pub fn elapsed_minutes(&self) -> Result<Option<u64>, PixelaClientError> {
+        let started = match fs::read_to_string(&self.path) {
+            Ok(v) => v,
+            Err(ref e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        let started = started.trim().parse()
+            .map(|v| UNIX_EPOCH + Duration::from_secs(v))
+            .map_err(|_| io::Error::new(ErrorKind::InvalidData, "stopwatch file is broken"))?;
+
+        let elapsed = SystemTime::now().duration_since(started).unwrap_or_default();
+        Ok(Some(elapsed.as_secs() / 60))
+    }
+
+    /// Discards the measurement.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when the file can not be removed.
+    pub fn reset(&self) -> Result<(), PixelaClientError> {
+        match fs::remove_file(&self.path) {
+            Err(ref e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            res => Ok(res?),
+        }
+    }
+
+    /// Stops the measurement and returns the elapsed minutes, or `None` when the stopwatch is not running.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when the file can not be read or removed.
+    pub fn stop(&self) -> Result<Option<u64>, PixelaClientError> {
+        let minutes = self.elapsed_minutes()?;
+        self.reset()?;
+
+        Ok(minutes)
+    }
+}
+
+#[cfg(test)]
+mod stopwatch_test {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn local_stopwatch_test() {
+        let path = env::temp_dir().join(format!("pixelast-stopwatch-{}", ::std::process::id()));
+        let stopwatch = LocalStopwatch::new(&path);
+
+        assert_eq!(stopwatch.stop().unwrap(), None);
+
+        stopwatch.start().unwrap();
+        assert!(stopwatch.is_running());
+
+        let started = fs::read_to_string(&path).unwrap();
+        fs::write(&path, (started.parse::<u64>().unwrap() - 37 * 60).to_string()).unwrap();
+
+        stopwatch.start().unwrap();
+        assert_eq!(stopwatch.stop().unwrap(), Some(37));
+        assert!(!stopwatch.is_running());
+    }
+}
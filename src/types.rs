@@ -0,0 +1,493 @@
+use super::error::PixelaClientError;
+use super::validation::{self, USERNAME_RULE, TOKEN_RULE, GRAPH_ID_RULE, PIXEL_DATE_RULE, QUANTITY_RULE, INT_QUANTITY_RULE, FLOAT_QUANTITY_RULE, CHANNEL_ID_RULE, NOTIFICATION_ID_RULE, WEBHOOK_HASH_RULE};
+use super::graph::GraphType;
+
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
+use serde::de::{self, Visitor};
+
+use std::fmt;
+use std::str::FromStr;
+#[cfg(feature = "chrono")]
+use std::convert::TryFrom;
+
+#[cfg(feature = "chrono")]
+use chrono::{Datelike, NaiveDate, TimeZone, Utc};
+#[cfg(feature = "chrono")]
+use chrono_tz::Tz;
+#[cfg(feature = "chrono")]
+use super::graph::Graph;
+#[cfg(feature = "chrono")]
+use super::validation::TIMEZONE_RULE;
+
+macro_rules! validated_string {
+    ($(#[$attr:meta])* $name:ident, $param:expr, $rule:expr) => {
+        validated_string!($(#[$attr])* $name, $param, $rule, validation::validate);
+    };
+    ($(#[$attr:meta])* $name:ident, $param:expr, $rule:expr, $validate:path) => {
+        $(#[$attr])*
+        #[derive(Serialize, Clone, PartialEq, Eq, Hash)]
+        #[serde(transparent)]
+        pub struct $name(String);
+
+        impl $name {
+            #[doc = "Validates the value and constructs a new instance."]
+            #[doc = ""]
+            #[doc = "# Errors"]
+            #[doc = ""]
+            #[doc = "This method fails with `PixelaClientError::InvalidParameter` naming the violated rule."]
+            pub fn new(value: &str) -> Result<Self, PixelaClientError> {
+                $validate($param, value, $rule)?;
+                Ok($name(value.to_owned()))
+            }
+
+            #[doc = "Returns the value as a string slice."]
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = PixelaClientError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                $name::new(s)
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+                let value = String::deserialize(deserializer)?;
+                $name::new(&value).map_err(de::Error::custom)
+            }
+        }
+    };
+}
+
+macro_rules! display_string {
+    ($name:ident) => {
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{}({:?})", stringify!($name), self.0)
+            }
+        }
+    };
+}
+
+validated_string!(
+    /// A name of a user in Pixela.
+    /// Validation rule: ^[a-z][a-z0-9-]{1,32}$
+    Username, "username", USERNAME_RULE
+);
+display_string!(Username);
+
+validated_string!(
+    /// A token of a user in Pixela. It is not shown by `Debug` to keep it out of logs.
+    /// Validation rule: ^[ -~]{8,128}$
+    Token, "token", TOKEN_RULE
+);
+
+impl fmt::Debug for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Token(***)")
+    }
+}
+
+validated_string!(
+    /// An ID for identifying the pixelation graph.
+    /// Validation rule: ^[a-z][a-z0-9-]{1,16}$
+    GraphId, "graph id", GRAPH_ID_RULE
+);
+display_string!(GraphId);
+
+validated_string!(
+    /// The date of a pixel in yyyyMMdd format.
+    /// Validation rule: ^[0-9]{4}(0[1-9]|1[0-2])(0[1-9]|[12][0-9]|3[01])$ and the day must exist in the calendar.
+    PixelDate, "date", PIXEL_DATE_RULE, validation::validate_date
+);
+display_string!(PixelDate);
+
+validated_string!(
+    /// An ID for identifying a notification channel.
+    /// Validation rule: ^[a-z][a-z0-9-]{1,16}$
+    ChannelId, "channel id", CHANNEL_ID_RULE
+);
+display_string!(ChannelId);
+
+validated_string!(
+    /// An ID for identifying a notification rule of a graph.
+    /// Validation rule: ^[a-z][a-z0-9-]{1,16}$
+    NotificationId, "notification id", NOTIFICATION_ID_RULE
+);
+display_string!(NotificationId);
+
+validated_string!(
+    /// A hash identifying a webhook. Anyone who knows it can invoke the webhook, so keep it secret like a token.
+    /// Validation rule: ^[0-9A-Za-z]{1,128}$
+    WebhookHash, "webhook hash", WEBHOOK_HASH_RULE
+);
+display_string!(WebhookHash);
+
+impl PixelDate {
+    /// Constructs a `PixelDate` from a year, a month and a day without formatting the string by hand.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with `PixelaClientError::InvalidParameter` when the day does not exist in the calendar.
+    pub fn from_ymd(year: u32, month: u32, day: u32) -> Result<Self, PixelaClientError> {
+        if year > 9999 {
+            return Err(PixelaClientError::InvalidParameter { name: "date", rule: PIXEL_DATE_RULE });
+        }
+
+        PixelDate::new(&format!("{:04}{:02}{:02}", year, month, day))
+    }
+
+    /// Returns the year, the month and the day.
+    pub fn ymd(&self) -> (u32, u32, u32) {
+        let year = self.0[0..4].parse().expect("year must be digits");
+        let month = self.0[4..6].parse().expect("month must be digits");
+        let day = self.0[6..8].parse().expect("day must be digits");
+
+        (year, month, day)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl PixelDate {
+    /// Returns the date as a `NaiveDate`.
+    pub fn to_naive_date(&self) -> NaiveDate {
+        let (year, month, day) = self.ymd();
+        NaiveDate::from_ymd_opt(year as i32, month, day).expect("pixel date must exist in the calendar")
+    }
+
+    /// Returns today in the given timezone.
+    pub fn today<T: TimeZone>(tz: &T) -> Self {
+        PixelDate::try_from(Utc::now().with_timezone(tz).naive_local().date()).expect("today must be a pixel date")
+    }
+
+    /// Returns today in the timezone named like `Asia/Tokyo`, as `Graph::timezone` holds it.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with `PixelaClientError::InvalidParameter` when the name is not in the tz database.
+    pub fn today_in(timezone: &str) -> Result<Self, PixelaClientError> {
+        let tz: Tz = timezone.parse().map_err(|_| PixelaClientError::InvalidParameter { name: "timezone", rule: TIMEZONE_RULE })?;
+        Ok(PixelDate::today(&tz))
+    }
+
+    /// Returns today in the timezone of the graph, which Pixela uses to decide the date of `increment` and `decrement`.
+    /// Pixela uses UTC when the graph has no timezone.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with `PixelaClientError::InvalidParameter` when the timezone of the graph is not in the tz database.
+    pub fn today_for(graph: &Graph) -> Result<Self, PixelaClientError> {
+        match graph.timezone {
+            Some(ref v) => PixelDate::today_in(v),
+            None => Ok(PixelDate::today(&Utc)),
+        }
+    }
+}
+
+/// Fails with `PixelaClientError::InvalidParameter` when the year is not between 0 and 9999.
+#[cfg(feature = "chrono")]
+impl TryFrom<NaiveDate> for PixelDate {
+    type Error = PixelaClientError;
+
+    fn try_from(date: NaiveDate) -> Result<Self, Self::Error> {
+        if date.year() < 0 {
+            return Err(PixelaClientError::InvalidParameter { name: "date", rule: PIXEL_DATE_RULE });
+        }
+
+        PixelDate::from_ymd(date.year() as u32, date.month(), date.day())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<'a> TryFrom<&'a NaiveDate> for PixelDate {
+    type Error = PixelaClientError;
+
+    fn try_from(date: &'a NaiveDate) -> Result<Self, Self::Error> {
+        PixelDate::try_from(*date)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<PixelDate> for NaiveDate {
+    fn from(date: PixelDate) -> Self {
+        date.to_naive_date()
+    }
+}
+
+/// The quantity of a pixel.
+///
+/// It is sent to Pixela in its string format, such as `"5"` or `"1.5"`.
+/// Validation rule: int^\-?[0-9]+ float^\-?[0-9]+\.[0-9]+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Quantity {
+    /// A quantity of an `int` graph.
+    Int(i64),
+    /// A quantity of a `float` graph.
+    Float(f64),
+}
+
+impl Quantity {
+    /// Parses the quantity from Pixela's string format.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with `PixelaClientError::InvalidParameter` naming the violated rule.
+    pub fn new(value: &str) -> Result<Self, PixelaClientError> {
+        validation::validate("quantity", value, QUANTITY_RULE)?;
+
+        let invalid = PixelaClientError::InvalidParameter { name: "quantity", rule: QUANTITY_RULE };
+        if value.contains('.') {
+            value.parse().map(Quantity::Float).map_err(|_| invalid)
+        } else {
+            value.parse().map(Quantity::Int).map_err(|_| invalid)
+        }
+    }
+
+    /// Returns the type of the graph the quantity belongs to.
+    pub fn graph_type(&self) -> GraphType {
+        match self {
+            Quantity::Int(_) => GraphType::Int,
+            Quantity::Float(_) => GraphType::Float,
+        }
+    }
+
+    /// Returns the quantity as an `f64`.
+    pub fn as_f64(&self) -> f64 {
+        match *self {
+            Quantity::Int(v) => v as f64,
+            Quantity::Float(v) => v,
+        }
+    }
+
+    /// Checks the quantity against the type of the target graph.
+    ///
+    /// An `Int` quantity is converted for a `float` graph, and a `Float` quantity is rejected for an `int` graph.
+    pub(crate) fn for_graph_type(self, graph_type: Option<GraphType>) -> Result<Self, PixelaClientError> {
+        match (self, graph_type) {
+            (Quantity::Float(v), _) if !v.is_finite() => Err(PixelaClientError::InvalidParameter { name: "quantity", rule: FLOAT_QUANTITY_RULE }),
+            (Quantity::Float(_), Some(GraphType::Int)) => Err(PixelaClientError::InvalidParameter { name: "quantity", rule: INT_QUANTITY_RULE }),
+            (Quantity::Int(v), Some(GraphType::Float)) => Ok(Quantity::Float(v as f64)),
+            (v, _) => Ok(v),
+        }
+    }
+}
+
+impl fmt::Display for Quantity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Quantity::Int(v) => write!(f, "{}", v),
+            Quantity::Float(v) if v.is_finite() && v.fract() == 0.0 => write!(f, "{:.1}", v),
+            Quantity::Float(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+impl FromStr for Quantity {
+    type Err = PixelaClientError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Quantity::new(s)
+    }
+}
+
+impl From<i64> for Quantity {
+    fn from(v: i64) -> Self {
+        Quantity::Int(v)
+    }
+}
+
+impl From<i32> for Quantity {
+    fn from(v: i32) -> Self {
+        Quantity::Int(i64::from(v))
+    }
+}
+
+impl From<f64> for Quantity {
+    fn from(v: f64) -> Self {
+        Quantity::Float(v)
+    }
+}
+
+impl Serialize for Quantity {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Quantity {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        deserializer.deserialize_any(QuantityVisitor)
+    }
+}
+
+struct QuantityVisitor;
+
+impl<'de> Visitor<'de> for QuantityVisitor {
+    type Value = Quantity;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a quantity as a number or a string")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Quantity, E> where E: de::Error {
+        Quantity::new(v).map_err(de::Error::custom)
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Quantity, E> where E: de::Error {
+        Ok(Quantity::Int(v))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Quantity, E> where E: de::Error {
+        if v > i64::MAX as u64 {
+            return Err(de::Error::custom("quantity is out of range"));
+        }
+
+        Ok(Quantity::Int(v as i64))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Quantity, E> where E: de::Error {
+        Ok(Quantity::Float(v))
+    }
+}
+
+#[cfg(test)]
+mod types_test {
+    use super::*;
+    use serde_json;
+
+    #[test]
+    fn webhook_hash_test() {
+        assert!(WebhookHash::new("0a1b2c3d4e5f").is_ok());
+        assert!(WebhookHash::new("").is_err());
+        assert!(WebhookHash::new("abc/../def").is_err());
+    }
+
+    #[test]
+    fn graph_id_test() {
+        assert!(GraphId::new("test-id1").is_ok());
+        assert!(GraphId::new("1testid").is_err());
+        assert!(GraphId::new("TestId").is_err());
+        assert!(GraphId::new("abcdefghijklmnopqr").is_err());
+
+        match GraphId::new("a") {
+            Err(PixelaClientError::InvalidParameter { name, rule }) => {
+                assert_eq!(name, "graph id");
+                assert_eq!(rule, GRAPH_ID_RULE);
+            },
+            _ => panic!("not failed."),
+        };
+    }
+
+    #[test]
+    fn username_test() {
+        assert!(Username::new("testuser").is_ok());
+        assert!(Username::new("test_user").is_err());
+    }
+
+    #[test]
+    fn pixel_date_test() {
+        assert!(PixelDate::new("20181017").is_ok());
+        assert!(PixelDate::new("2018-10-17").is_err());
+        assert!(PixelDate::new("20181317").is_err());
+        assert!(PixelDate::new("20181032").is_err());
+        assert!(PixelDate::new("20180229").is_err());
+
+        let date = PixelDate::from_ymd(2018, 10, 7).unwrap();
+        assert_eq!(date.as_str(), "20181007");
+        assert_eq!(date.ymd(), (2018, 10, 7));
+        assert!(PixelDate::from_ymd(2018, 2, 30).is_err());
+        assert!(PixelDate::from_ymd(10000, 1, 1).is_err());
+    }
+
+    #[test]
+    fn validated_string_deserialize_test() {
+        let date: PixelDate = serde_json::from_str(r#""20181017""#).unwrap();
+        assert_eq!(date.ymd(), (2018, 10, 17));
+
+        assert!(serde_json::from_str::<PixelDate>(r#""2018""#).is_err());
+        assert!(serde_json::from_str::<GraphId>(r#""1testid""#).is_err());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn pixel_date_chrono_test() {
+        use chrono::FixedOffset;
+
+        let date = NaiveDate::from_ymd_opt(2018, 10, 7).unwrap();
+        assert_eq!(PixelDate::try_from(date).unwrap().as_str(), "20181007");
+        assert!(PixelDate::try_from(NaiveDate::from_ymd_opt(10000, 1, 1).unwrap()).is_err());
+        assert!(PixelDate::try_from(NaiveDate::from_ymd_opt(-1, 1, 1).unwrap()).is_err());
+        assert_eq!(PixelDate::new("20181007").unwrap().to_naive_date(), date);
+
+        let utc = PixelDate::today(&Utc);
+        let ahead = PixelDate::today(&FixedOffset::east_opt(14 * 3600).unwrap());
+        assert!(ahead.as_str() >= utc.as_str());
+
+        assert_eq!(PixelDate::today_in("Pacific/Kiritimati").unwrap(), ahead);
+        assert!(PixelDate::today_in("Asia/Nowhere").is_err());
+    }
+
+    #[test]
+    fn quantity_test() {
+        assert_eq!(Quantity::new("10").unwrap(), Quantity::Int(10));
+        assert_eq!(Quantity::new("-10").unwrap(), Quantity::Int(-10));
+        assert_eq!(Quantity::new("1.5").unwrap(), Quantity::Float(1.5));
+        assert!(Quantity::new("1.").is_err());
+        assert!(Quantity::new("ten").is_err());
+        assert!(Quantity::new("99999999999999999999").is_err());
+
+        assert_eq!(Quantity::Int(5).to_string(), "5");
+        assert_eq!(Quantity::Float(5.0).to_string(), "5.0");
+        assert_eq!(Quantity::Float(-0.25).to_string(), "-0.25");
+    }
+
+    #[test]
+    fn quantity_graph_type_test() {
+        assert_eq!(Quantity::Int(5).for_graph_type(Some(GraphType::Float)).unwrap(), Quantity::Float(5.0));
+        assert_eq!(Quantity::Int(5).for_graph_type(Some(GraphType::Int)).unwrap(), Quantity::Int(5));
+        assert_eq!(Quantity::Float(1.5).for_graph_type(None).unwrap(), Quantity::Float(1.5));
+        assert!(Quantity::Float(f64::NAN).for_graph_type(None).is_err());
+
+        match Quantity::Float(1.5).for_graph_type(Some(GraphType::Int)) {
+            Err(PixelaClientError::InvalidParameter { rule, .. }) => assert_eq!(rule, INT_QUANTITY_RULE),
+            _ => panic!("not failed."),
+        };
+    }
+
+    #[test]
+    fn quantity_serde_test() {
+        assert_eq!(serde_json::to_string(&Quantity::Float(1.5)).unwrap(), r#""1.5""#);
+        assert_eq!(serde_json::from_str::<Quantity>(r#""7""#).unwrap(), Quantity::Int(7));
+        assert_eq!(serde_json::from_str::<Quantity>("7").unwrap(), Quantity::Int(7));
+        assert_eq!(serde_json::from_str::<Quantity>("7.25").unwrap(), Quantity::Float(7.25));
+        assert!(serde_json::from_str::<Quantity>(r#""seven""#).is_err());
+    }
+
+    #[test]
+    fn token_debug_test() {
+        let token = Token::new("testtoken").unwrap();
+        assert_eq!(format!("{:?}", token), "Token(***)");
+    }
+
+    #[test]
+    fn serialize_test() {
+        let date = PixelDate::new("20181017").unwrap();
+        assert_eq!(serde_json::to_string(&date).unwrap(), r#""20181017""#);
+    }
+}
@@ -1,17 +1,19 @@
 use super::Authentication;
-use super::endpoint;
+use super::endpoint::Endpoint;
 use super::response;
-use super::http_client::{HttpClient, RequestContext, MethodType};
+use super::api_call::ApiCall;
+use super::error::PixelaClientError;
+use super::types::{Username, Token, GraphId};
+use super::http_client::{RequestContext, HttpResponse, MethodType};
 
-use failure::Error;
 use std::collections::HashMap;
 use serde_json;
 
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateUserParam {
-    pub token: String,
-    pub username: String,
+    pub token: Token,
+    pub username: Username,
     pub agree_terms_of_service: ConsentAnswer,
     pub not_minor: ConsentAnswer,
 }
@@ -25,8 +27,101 @@ pub enum ConsentAnswer {
     No,
 }
 
-pub(crate) fn create<T>(param: &CreateUserParam) -> Result<(), Error> where T: HttpClient {
-    let uri = endpoint::users();
+/// Changes to the public profile page of a user for `update_profile`. Only the fields which are set are sent to Pixela.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let update = ProfileUpdate::new()
+///     .display_name("Pixela Taro")
+///     .pinned_graph_id(&graph_id);
+/// client.update_profile(&update)?;
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ProfileUpdate {
+    #[serde(rename = "displayName", skip_serializing_if = "Option::is_none")]
+    display_name: Option<String>,
+    #[serde(rename = "gravatarIconEmail", skip_serializing_if = "Option::is_none")]
+    gravatar_icon_email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timezone: Option<String>,
+    #[serde(rename = "aboutURL", skip_serializing_if = "Option::is_none")]
+    about_url: Option<String>,
+    #[serde(rename = "contributeURLs", skip_serializing_if = "Option::is_none")]
+    contribute_urls: Option<Vec<String>>,
+    #[serde(rename = "pinnedGraphID", skip_serializing_if = "Option::is_none")]
+    pinned_graph_id: Option<GraphId>,
+    #[serde(rename = "githubUsername", skip_serializing_if = "Option::is_none")]
+    github_username: Option<String>,
+    #[serde(rename = "twitterUsername", skip_serializing_if = "Option::is_none")]
+    twitter_username: Option<String>,
+}
+
+impl ProfileUpdate {
+    /// Constructs a new `ProfileUpdate` which changes nothing.
+    pub fn new() -> Self {
+        ProfileUpdate::default()
+    }
+
+    /// Sets the name shown on the profile page.
+    pub fn display_name(mut self, display_name: &str) -> Self {
+        self.display_name = Some(display_name.to_owned());
+        self
+    }
+
+    /// Sets the email address registered on Gravatar, whose icon is shown as the profile image.
+    pub fn gravatar_icon_email(mut self, email: &str) -> Self {
+        self.gravatar_icon_email = Some(email.to_owned());
+        self
+    }
+
+    /// Sets the title shown under the display name.
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_owned());
+        self
+    }
+
+    /// Sets the timezone of the user, such as `Asia/Tokyo`.
+    pub fn timezone(mut self, timezone: &str) -> Self {
+        self.timezone = Some(timezone.to_owned());
+        self
+    }
+
+    /// Sets the URL of a page about the user.
+    pub fn about_url(mut self, url: &str) -> Self {
+        self.about_url = Some(url.to_owned());
+        self
+    }
+
+    /// Sets the URLs of the projects the user contributes to.
+    pub fn contribute_urls(mut self, urls: &[&str]) -> Self {
+        self.contribute_urls = Some(urls.iter().map(|v| v.to_string()).collect());
+        self
+    }
+
+    /// Sets the graph pinned at the top of the profile page.
+    pub fn pinned_graph_id(mut self, graph_id: &GraphId) -> Self {
+        self.pinned_graph_id = Some(graph_id.to_owned());
+        self
+    }
+
+    /// Sets the GitHub username linked from the profile page.
+    pub fn github_username(mut self, username: &str) -> Self {
+        self.github_username = Some(username.to_owned());
+        self
+    }
+
+    /// Sets the Twitter username linked from the profile page.
+    pub fn twitter_username(mut self, username: &str) -> Self {
+        self.twitter_username = Some(username.to_owned());
+        self
+    }
+}
+
+pub(crate) fn create(endpoint: &Endpoint, param: &CreateUserParam) -> Result<ApiCall<()>, PixelaClientError> {
+    let uri = endpoint.users();
     let body = serde_json::to_string(param)?;
     let context = RequestContext::new(
         &uri,
@@ -35,38 +130,68 @@ pub(crate) fn create<T>(param: &CreateUserParam) -> Result<(), Error> where T: H
         None,
     );
 
-    let body = T::do_request(&context)?;
-    response::build_result(&body)
+    Ok(ApiCall::new(context, response::build_result).non_idempotent())
 }
 
-pub(crate) fn update<T>(auth: &Authentication, new_token: &str) -> Result<(), Error> where T: HttpClient {
+/// Not retried after a server error, which may have changed the token already.
+pub(crate) fn update(endpoint: &Endpoint, auth: &Authentication, new_token: &Token) -> Result<ApiCall<()>, PixelaClientError> {
     let mut hash: HashMap<&str, &str> = HashMap::new();
-    hash.insert("newToken", new_token);
+    hash.insert("newToken", new_token.as_str());
 
-    let uri = endpoint::user(&auth.username);
+    let uri = endpoint.user(auth.username.as_str());
     let body = serde_json::to_string(&hash)?;
     let context = RequestContext::new(
         &uri,
         MethodType::Put,
         Some(&body),
-        Some(&auth.token),
+        Some(auth.token.as_str()),
     );
 
-    let body = T::do_request(&context)?;
-    response::build_result(&body)
+    Ok(ApiCall::new(context, response::build_result).non_idempotent())
 }
 
-pub(crate) fn delete<T>(auth: &Authentication) -> Result<(), Error> where T: HttpClient {
-    let uri = endpoint::user(&auth.username);
+pub(crate) fn delete(endpoint: &Endpoint, auth: &Authentication) -> Result<ApiCall<()>, PixelaClientError> {
+    let uri = endpoint.user(auth.username.as_str());
     let context = RequestContext::new(
         &uri,
         MethodType::Delete,
         None,
-        Some(&auth.token),
+        Some(auth.token.as_str()),
     );
 
-    let body = T::do_request(&context)?;
-    response::build_result(&body)
+    Ok(ApiCall::new(context, response::build_result).non_idempotent())
+}
+
+pub(crate) fn update_profile(endpoint: &Endpoint, auth: &Authentication, update: &ProfileUpdate) -> Result<ApiCall<()>, PixelaClientError> {
+    let uri = endpoint.profile(auth.username.as_str());
+    let body = serde_json::to_string(update)?;
+    let context = RequestContext::new(
+        &uri,
+        MethodType::Put,
+        Some(&body),
+        Some(auth.token.as_str()),
+    );
+
+    Ok(ApiCall::new(context, response::build_result))
+}
+
+/// The profile page is public, so the token is not sent.
+pub(crate) fn get_profile_page(endpoint: &Endpoint, username: &Username) -> Result<ApiCall<String>, PixelaClientError> {
+    let uri = endpoint.profile(username.as_str());
+    let context = RequestContext::new(
+        &uri,
+        MethodType::Get,
+        None,
+        None,
+    );
+
+    Ok(ApiCall::new(context, parse_profile_page))
+}
+
+fn parse_profile_page(response: &HttpResponse) -> Result<String, PixelaClientError> {
+    response::check_error(response)?;
+
+    Ok(response.body.to_owned())
 }
 
 #[cfg(test)]
@@ -76,8 +201,8 @@ mod user_test {
     #[test]
     fn create_user_param_serialize_test() {
         let param = CreateUserParam {
-            token: "testtoken".to_owned(),
-            username: "testuser".to_owned(),
+            token: Token::new("testtoken").unwrap(),
+            username: Username::new("testuser").unwrap(),
             agree_terms_of_service: ConsentAnswer::Yes,
             not_minor: ConsentAnswer::No,
         };
@@ -89,4 +214,25 @@ mod user_test {
             panic!("failed serialize params");
         };
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn profile_update_serialize_test() {
+        let param = ProfileUpdate::new()
+            .display_name("Test User")
+            .contribute_urls(&["https://github.com/a-know/Pixela"])
+            .pinned_graph_id(&GraphId::new("testid").unwrap())
+            .github_username("testuser");
+
+        let res = serde_json::to_string(&param);
+        if let Ok(v) = res {
+            assert_eq!(v, r#"{"displayName":"Test User","contributeURLs":["https://github.com/a-know/Pixela"],"pinnedGraphID":"testid","githubUsername":"testuser"}"#);
+        } else {
+            panic!("failed serialize params");
+        };
+    }
+
+    #[test]
+    fn empty_profile_update_serialize_test() {
+        assert_eq!(serde_json::to_string(&ProfileUpdate::new()).unwrap(), "{}");
+    }
+}
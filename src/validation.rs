@@ -0,0 +1,169 @@
+use super::error::PixelaClientError;
+
+use regex::Regex;
+use reqwest::Url;
+
+use std::sync::OnceLock;
+
+/// Validation rule of a username in Pixela.
+pub const USERNAME_RULE: &str = r"^[a-z][a-z0-9-]{1,32}$";
+/// Validation rule of a token in Pixela.
+pub const TOKEN_RULE: &str = r"^[ -~]{8,128}$";
+/// Validation rule of a graph id in Pixela.
+pub const GRAPH_ID_RULE: &str = r"^[a-z][a-z0-9-]{1,16}$";
+/// Validation rule of a pixel date in Pixela. It is yyyyMMdd format.
+pub const PIXEL_DATE_RULE: &str = r"^[0-9]{4}(0[1-9]|1[0-2])(0[1-9]|[12][0-9]|3[01])$";
+/// Validation rule of a quantity in Pixela. It is an int or a float.
+pub const QUANTITY_RULE: &str = r"^\-?[0-9]+(\.[0-9]+)?$";
+/// Validation rule of a quantity of an `int` graph in Pixela.
+pub const INT_QUANTITY_RULE: &str = r"^\-?[0-9]+$";
+/// Validation rule of a quantity of a `float` graph in Pixela.
+pub const FLOAT_QUANTITY_RULE: &str = r"^\-?[0-9]+\.[0-9]+$";
+/// Validation rule of the timezone of a graph in Pixela.
+pub const TIMEZONE_RULE: &str = "a name in the tz database such as Asia/Tokyo";
+/// Validation rule of a channel id in Pixela.
+pub const CHANNEL_ID_RULE: &str = r"^[a-z][a-z0-9-]{1,16}$";
+/// Validation rule of a notification rule id in Pixela.
+pub const NOTIFICATION_ID_RULE: &str = r"^[a-z][a-z0-9-]{1,16}$";
+/// Validation rule of a webhook hash in Pixela.
+pub const WEBHOOK_HASH_RULE: &str = r"^[0-9A-Za-z]{1,128}$";
+/// Validation rule of the optional data of a pixel in Pixela.
+pub const OPTIONAL_DATA_RULE: &str = "JSON of up to 10240 bytes";
+/// Validation rule of the purge cache URLs of a graph in Pixela.
+pub const PURGE_CACHE_URLS_RULE: &str = "up to 5 URLs starting with https://";
+/// Validation rule of the base url of a Pixela server.
+pub const BASE_URL_RULE: &str = "an absolute URL starting with http:// or https://";
+
+const OPTIONAL_DATA_MAX_BYTES: usize = 10240;
+const PURGE_CACHE_URLS_MAX_COUNT: usize = 5;
+
+/// Validates the value against the rule, naming the parameter and the rule when it is violated.
+pub(crate) fn validate(name: &'static str, value: &str, rule: &'static str) -> Result<(), PixelaClientError> {
+    if !compiled(rule).is_match(value) {
+        return Err(PixelaClientError::InvalidParameter { name, rule });
+    }
+
+    Ok(())
+}
+
+/// Returns the regex of the rule, which is compiled on its first use only.
+fn compiled(rule: &'static str) -> &'static Regex {
+    macro_rules! compiled_rules {
+        ($($rule:ident),*) => {
+            $(
+                if rule == $rule {
+                    static REGEX: OnceLock<Regex> = OnceLock::new();
+                    return REGEX.get_or_init(|| Regex::new($rule).expect("validation rule must be a valid regex"));
+                }
+            )*
+        };
+    }
+
+    compiled_rules!(USERNAME_RULE, TOKEN_RULE, GRAPH_ID_RULE, PIXEL_DATE_RULE, QUANTITY_RULE, INT_QUANTITY_RULE, FLOAT_QUANTITY_RULE, CHANNEL_ID_RULE, NOTIFICATION_ID_RULE, WEBHOOK_HASH_RULE);
+    panic!("validation rule must be a regex rule of this module: {}", rule)
+}
+
+/// Validates the value as a pixel date, rejecting days which do not exist in the calendar such as `20180231`.
+pub(crate) fn validate_date(name: &'static str, value: &str, rule: &'static str) -> Result<(), PixelaClientError> {
+    validate(name, value, rule)?;
+
+    let year: u32 = value[0..4].parse().expect("year must be digits");
+    let month: u32 = value[4..6].parse().expect("month must be digits");
+    let day: u32 = value[6..8].parse().expect("day must be digits");
+
+    if day > days_in_month(year, month) {
+        return Err(PixelaClientError::InvalidParameter { name, rule });
+    }
+
+    Ok(())
+}
+
+/// Validates the optional data of a pixel, which Pixela limits to 10KB.
+pub(crate) fn validate_optional_data(value: &str) -> Result<(), PixelaClientError> {
+    if value.len() > OPTIONAL_DATA_MAX_BYTES {
+        return Err(PixelaClientError::InvalidParameter { name: "optional data", rule: OPTIONAL_DATA_RULE });
+    }
+
+    Ok(())
+}
+
+/// Validates the purge cache URLs of a graph, which Pixela limits to 5 https URLs.
+pub(crate) fn validate_purge_cache_urls(urls: &[String]) -> Result<(), PixelaClientError> {
+    if urls.len() > PURGE_CACHE_URLS_MAX_COUNT || urls.iter().any(|v| !v.starts_with("https://")) {
+        return Err(PixelaClientError::InvalidParameter { name: "purge cache urls", rule: PURGE_CACHE_URLS_RULE });
+    }
+
+    Ok(())
+}
+
+/// Validates the base url of a Pixela server, so that a malformed url fails when a client is built.
+pub(crate) fn validate_base_url(value: &str) -> Result<(), PixelaClientError> {
+    match Url::parse(value) {
+        Ok(ref url) if (url.scheme() == "http" || url.scheme() == "https") && url.has_host() => Ok(()),
+        _ => Err(PixelaClientError::InvalidParameter { name: "base url", rule: BASE_URL_RULE }),
+    }
+}
+
+pub(crate) fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+#[cfg(test)]
+mod validation_test {
+    use super::*;
+
+    #[test]
+    fn compiled_test() {
+        assert!(::std::ptr::eq(compiled(TOKEN_RULE), compiled(TOKEN_RULE)));
+        assert_eq!(compiled(PIXEL_DATE_RULE).as_str(), PIXEL_DATE_RULE);
+    }
+
+    #[test]
+    fn validate_base_url_test() {
+        assert!(validate_base_url("https://pixe.la").is_ok());
+        assert!(validate_base_url("http://localhost:8080/").is_ok());
+        assert!(validate_base_url("localhost:8080").is_err());
+        assert!(validate_base_url("pixe.la").is_err());
+        assert!(validate_base_url("ftp://pixe.la").is_err());
+        assert!(validate_base_url("").is_err());
+    }
+
+    #[test]
+    fn validate_token_test() {
+        assert!(validate("token", "testtoken", TOKEN_RULE).is_ok());
+        assert!(validate("token", "short", TOKEN_RULE).is_err());
+        assert!(validate("token", "test\ntoken", TOKEN_RULE).is_err());
+        assert!(validate("token", "testtōken", TOKEN_RULE).is_err());
+        assert!(validate("token", &"a".repeat(129), TOKEN_RULE).is_err());
+    }
+
+    #[test]
+    fn validate_date_test() {
+        assert!(validate_date("date", "20181017", PIXEL_DATE_RULE).is_ok());
+        assert!(validate_date("date", "20160229", PIXEL_DATE_RULE).is_ok());
+        assert!(validate_date("date", "20180229", PIXEL_DATE_RULE).is_err());
+        assert!(validate_date("date", "21000229", PIXEL_DATE_RULE).is_err());
+        assert!(validate_date("date", "20180431", PIXEL_DATE_RULE).is_err());
+        assert!(validate_date("date", "2018-10-17", PIXEL_DATE_RULE).is_err());
+    }
+
+    #[test]
+    fn validate_optional_data_test() {
+        assert!(validate_optional_data(&"a".repeat(10240)).is_ok());
+        assert!(validate_optional_data(&"a".repeat(10241)).is_err());
+    }
+
+    #[test]
+    fn validate_purge_cache_urls_test() {
+        let urls = vec!["https://camo.githubusercontent.com/xxxx".to_owned(); 5];
+        assert!(validate_purge_cache_urls(&urls).is_ok());
+        assert!(validate_purge_cache_urls(&[]).is_ok());
+        assert!(validate_purge_cache_urls(&vec!["https://camo.githubusercontent.com/xxxx".to_owned(); 6]).is_err());
+        assert!(validate_purge_cache_urls(&["http://example.com/graph.svg".to_owned()]).is_err());
+    }
+}
@@ -0,0 +1,148 @@
+use super::Authentication;
+use super::endpoint::Endpoint;
+use super::response;
+use super::api_call::ApiCall;
+use super::http_client::{RequestContext, HttpResponse, MethodType, HeaderType};
+use super::error::PixelaClientError;
+use super::types::{Username, GraphId, WebhookHash};
+
+use serde_json;
+
+/// What a webhook does to the pixel of today when it is invoked.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookType {
+    #[serde(rename = "increment")]
+    Increment,
+    #[serde(rename = "decrement")]
+    Decrement,
+    #[serde(rename = "stopwatch")]
+    Stopwatch,
+}
+
+/// A webhook registered in Pixela.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Webhook {
+    /// The hash to invoke the webhook with.
+    #[serde(rename = "webhookHash")]
+    pub hash: WebhookHash,
+    /// The graph updated by the webhook.
+    #[serde(rename = "graphID")]
+    pub graph_id: GraphId,
+    /// What the webhook does to the graph.
+    #[serde(rename = "type")]
+    pub webhook_type: WebhookType,
+}
+
+#[derive(Serialize, Debug)]
+struct CreateWebhookParam<'a> {
+    #[serde(rename = "graphID")]
+    graph_id: &'a GraphId,
+    #[serde(rename = "type")]
+    webhook_type: WebhookType,
+}
+
+#[derive(Deserialize, Debug)]
+struct CreatedWebhook {
+    #[serde(rename = "webhookHash")]
+    hash: WebhookHash,
+}
+
+#[derive(Deserialize, Debug)]
+struct Webhooks {
+    webhooks: Vec<Webhook>,
+}
+
+pub(crate) fn create(endpoint: &Endpoint, auth: &Authentication, graph_id: &GraphId, webhook_type: WebhookType) -> Result<ApiCall<WebhookHash>, PixelaClientError> {
+    let param = CreateWebhookParam { graph_id, webhook_type };
+
+    let uri = endpoint.webhooks(auth.username.as_str());
+    let body = serde_json::to_string(&param)?;
+    let context = RequestContext::new(
+        &uri,
+        MethodType::Post,
+        Some(&body),
+        Some(auth.token.as_str()),
+    );
+
+    Ok(ApiCall::new(context, parse_created_webhook).non_idempotent())
+}
+
+/// Pixela returns the hash alongside the usual success message.
+fn parse_created_webhook(response: &HttpResponse) -> Result<WebhookHash, PixelaClientError> {
+    response::build_result(response)?;
+
+    let res: CreatedWebhook = serde_json::from_str(&response.body)?;
+    Ok(res.hash)
+}
+
+pub(crate) fn get_all(endpoint: &Endpoint, auth: &Authentication) -> Result<ApiCall<Vec<Webhook>>, PixelaClientError> {
+    let uri = endpoint.webhooks(auth.username.as_str());
+    let context = RequestContext::new(
+        &uri,
+        MethodType::Get,
+        None,
+        Some(auth.token.as_str()),
+    );
+
+    Ok(ApiCall::new(context, parse_webhooks))
+}
+
+fn parse_webhooks(response: &HttpResponse) -> Result<Vec<Webhook>, PixelaClientError> {
+    response::check_error(response)?;
+
+    let res: Webhooks = serde_json::from_str(&response.body)?;
+    Ok(res.webhooks)
+}
+
+/// Invoking a webhook needs only the username and the hash, not the token.
+pub(crate) fn invoke(endpoint: &Endpoint, username: &Username, webhook_hash: &WebhookHash) -> Result<ApiCall<()>, PixelaClientError> {
+    let uri = endpoint.webhook(username.as_str(), webhook_hash.as_str());
+    let mut context = RequestContext::new(
+        &uri,
+        MethodType::Post,
+        None,
+        None,
+    );
+
+    context.insert_header(HeaderType::ContentLength, "0");
+
+    Ok(ApiCall::new(context, response::build_result).non_idempotent())
+}
+
+pub(crate) fn delete(endpoint: &Endpoint, auth: &Authentication, webhook_hash: &WebhookHash) -> Result<ApiCall<()>, PixelaClientError> {
+    let uri = endpoint.webhook(auth.username.as_str(), webhook_hash.as_str());
+    let context = RequestContext::new(
+        &uri,
+        MethodType::Delete,
+        None,
+        Some(auth.token.as_str()),
+    );
+
+    Ok(ApiCall::new(context, response::build_result).non_idempotent())
+}
+
+#[cfg(test)]
+mod webhook_test {
+    use super::*;
+
+    #[test]
+    fn create_webhook_param_serialize_test() {
+        let graph_id = GraphId::new("testid").unwrap();
+        let param = CreateWebhookParam { graph_id: &graph_id, webhook_type: WebhookType::Stopwatch };
+
+        let json = serde_json::to_string(&param).unwrap();
+        assert_eq!(json, r#"{"graphID":"testid","type":"stopwatch"}"#);
+    }
+
+    #[test]
+    fn webhooks_deserialize_test() {
+        let json = r#"{"webhooks":[{"webhookHash":"0a1b2c","graphID":"testid","type":"increment"}]}"#;
+
+        let res = parse_webhooks(&HttpResponse::new(200, json)).unwrap();
+        assert_eq!(res, vec![Webhook {
+            hash: WebhookHash::new("0a1b2c").unwrap(),
+            graph_id: GraphId::new("testid").unwrap(),
+            webhook_type: WebhookType::Increment,
+        }]);
+    }
+}
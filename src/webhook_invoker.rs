@@ -0,0 +1,222 @@
+use super::Transport;
+use super::endpoint::{Endpoint, DEFAULT_BASE_URL, DEFAULT_API_VERSION};
+use super::api_call::send_async;
+use super::http_client::{HttpClient, TinyHttpClient, AsyncHttpClient, TinyAsyncHttpClient, PixelaFuture};
+use super::retry::RetryPolicy;
+use super::error::PixelaClientError;
+use super::webhook;
+use super::validation;
+use super::types::{Username, WebhookHash};
+
+use std::sync::Arc;
+
+/// A `WebhookInvoker` invokes the webhooks of a user without the token, for example from a CI pipeline.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let invoker = WebhookInvoker::builder("username")
+///     .base_url("http://localhost:8080")
+///     .build()?;
+/// invoker.invoke(&"webhookhash".parse()?)?;
+/// ```
+pub struct WebhookInvoker<C = TinyHttpClient> {
+    username: Username,
+    endpoint: Endpoint,
+    http_client: C,
+    retry_policy: RetryPolicy,
+}
+
+/// An asynchronous `WebhookInvoker`. With the default `TinyAsyncHttpClient`, the futures must be run on a tokio runtime.
+pub struct AsyncWebhookInvoker<C = TinyAsyncHttpClient> {
+    username: Username,
+    endpoint: Endpoint,
+    http_client: Arc<C>,
+    retry_policy: RetryPolicy,
+}
+
+/// A `WebhookInvokerBuilder` can be used to create a `WebhookInvoker` or an `AsyncWebhookInvoker` with custom configuration.
+pub struct WebhookInvokerBuilder<C = TinyHttpClient> {
+    username: String,
+    base_url: String,
+    api_version: String,
+    http_client: Transport<C>,
+    retry_policy: RetryPolicy,
+}
+
+impl WebhookInvoker<TinyHttpClient> {
+    /// Creates a `WebhookInvokerBuilder` to configure a `WebhookInvoker` of the user.
+    pub fn builder(username: &str) -> WebhookInvokerBuilder {
+        WebhookInvokerBuilder::new(username)
+    }
+}
+
+impl<C> WebhookInvoker<C> where C: HttpClient {
+    /// Invoke a webhook of the user.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when request not success in Pixela.
+    pub fn invoke(&self, webhook_hash: &WebhookHash) -> Result<(), PixelaClientError> {
+        webhook::invoke(&self.endpoint, &self.username, webhook_hash)?.send(&self.http_client, &self.retry_policy)
+    }
+}
+
+impl AsyncWebhookInvoker<TinyAsyncHttpClient> {
+    /// Creates a `WebhookInvokerBuilder` to configure an `AsyncWebhookInvoker` of the user.
+    pub fn builder(username: &str) -> WebhookInvokerBuilder<TinyAsyncHttpClient> {
+        WebhookInvokerBuilder::new(username).default_http_client(|| TinyHttpClient::builder().build_async())
+    }
+}
+
+impl<C> AsyncWebhookInvoker<C> where C: AsyncHttpClient + Send + Sync + 'static {
+    /// Invoke a webhook of the user.
+    ///
+    /// # Errors
+    ///
+    /// The future fails when request not success in Pixela.
+    pub fn invoke(&self, webhook_hash: &WebhookHash) -> PixelaFuture<()> {
+        send_async(&self.http_client, &self.retry_policy, webhook::invoke(&self.endpoint, &self.username, webhook_hash))
+    }
+}
+
+impl WebhookInvokerBuilder<TinyHttpClient> {
+    /// Constructs a new `WebhookInvokerBuilder` targeting the Pixela production server.
+    pub fn new(username: &str) -> Self {
+        Self {
+            username: username.to_owned(),
+            base_url: DEFAULT_BASE_URL.to_owned(),
+            api_version: DEFAULT_API_VERSION.to_owned(),
+            http_client: Transport::Default(|| TinyHttpClient::builder().build()),
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+}
+
+impl<C> WebhookInvokerBuilder<C> {
+    /// Sets the base url of the Pixela server. Default is `https://pixe.la`.
+    pub fn base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.to_owned();
+        self
+    }
+
+    /// Sets the api version prefix appended to the base url. Default is `v1`.
+    pub fn api_version(mut self, api_version: &str) -> Self {
+        self.api_version = api_version.to_owned();
+        self
+    }
+
+    /// Sets the `HttpClient` or `AsyncHttpClient` used to send requests. Default is `TinyHttpClient`.
+    pub fn http_client<D>(self, http_client: D) -> WebhookInvokerBuilder<D> {
+        self.transport(Transport::Given(http_client))
+    }
+
+    /// Sets the `RetryPolicy` of rejected requests. Invocations are not retried after other failures.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Returns a `WebhookInvoker` that uses this `WebhookInvokerBuilder` configuration.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when the username violates the validation rule of Pixela,
+    /// when the base url is not an absolute http or https URL, or when the default `TinyHttpClient` cannot be built.
+    pub fn build(self) -> Result<WebhookInvoker<C>, PixelaClientError> where C: HttpClient {
+        Ok(WebhookInvoker {
+            username: Username::new(&self.username)?,
+            endpoint: self.endpoint()?,
+            http_client: self.http_client.build()?,
+            retry_policy: self.retry_policy,
+        })
+    }
+
+    /// Returns an `AsyncWebhookInvoker` that uses this `WebhookInvokerBuilder` configuration.
+    ///
+    /// # Errors
+    ///
+    /// This method fails when the username violates the validation rule of Pixela,
+    /// when the base url is not an absolute http or https URL, or when the default `TinyAsyncHttpClient` cannot be built.
+    pub fn build_async(self) -> Result<AsyncWebhookInvoker<C>, PixelaClientError> where C: AsyncHttpClient + Send + Sync + 'static {
+        Ok(AsyncWebhookInvoker {
+            username: Username::new(&self.username)?,
+            endpoint: self.endpoint()?,
+            http_client: Arc::new(self.http_client.build()?),
+            retry_policy: self.retry_policy,
+        })
+    }
+
+    fn endpoint(&self) -> Result<Endpoint, PixelaClientError> {
+        validation::validate_base_url(&self.base_url)?;
+        Ok(Endpoint::new(&self.base_url, &self.api_version))
+    }
+
+    fn default_http_client<D>(self, build: fn() -> Result<D, PixelaClientError>) -> WebhookInvokerBuilder<D> {
+        self.transport(Transport::Default(build))
+    }
+
+    fn transport<D>(self, http_client: Transport<D>) -> WebhookInvokerBuilder<D> {
+        WebhookInvokerBuilder {
+            username: self.username,
+            base_url: self.base_url,
+            api_version: self.api_version,
+            http_client,
+            retry_policy: self.retry_policy,
+        }
+    }
+}
+
+#[cfg(test)]
+mod webhook_invoker_test {
+    use super::*;
+    use super::super::{RequestContext, HttpResponse};
+    use futures::{future, Future};
+    use std::cell::RefCell;
+    use std::sync::Mutex;
+
+    struct RecordingHttpClient {
+        uris: RefCell<Vec<String>>,
+    }
+
+    impl HttpClient for RecordingHttpClient {
+        fn do_request(&self, context: &RequestContext) -> Result<HttpResponse, PixelaClientError> {
+            self.uris.borrow_mut().push(context.url().unwrap());
+            Ok(HttpResponse::new(200, r#"{"message":"Success.","isSuccess":true}"#))
+        }
+    }
+
+    struct RecordingAsyncHttpClient {
+        uris: Mutex<Vec<String>>,
+    }
+
+    impl AsyncHttpClient for RecordingAsyncHttpClient {
+        fn do_request(&self, context: &RequestContext) -> PixelaFuture<HttpResponse> {
+            self.uris.lock().unwrap().push(context.url().unwrap());
+            Box::new(future::ok(HttpResponse::new(200, r#"{"message":"Success.","isSuccess":true}"#)))
+        }
+    }
+
+    #[test]
+    fn invoke_test() {
+        let hash = WebhookHash::new("0a1b2c").unwrap();
+
+        let invoker = WebhookInvoker::builder("testuser")
+            .base_url("http://localhost:8080")
+            .http_client(RecordingHttpClient { uris: RefCell::new(vec![]) })
+            .build()
+            .unwrap();
+        invoker.invoke(&hash).unwrap();
+        assert_eq!(*invoker.http_client.uris.borrow(), vec!["http://localhost:8080/v1/users/testuser/webhooks/0a1b2c"]);
+
+        let invoker = AsyncWebhookInvoker::builder("testuser")
+            .http_client(RecordingAsyncHttpClient { uris: Mutex::new(vec![]) })
+            .build_async()
+            .unwrap();
+        invoker.invoke(&hash).wait().unwrap();
+        assert_eq!(*invoker.http_client.uris.lock().unwrap(), vec!["https://pixe.la/v1/users/testuser/webhooks/0a1b2c"]);
+
+        assert!(WebhookInvoker::builder("test_user").build().is_err());
+        assert!(WebhookInvoker::builder("testuser").base_url("localhost:8080").build().is_err());
+    }
+}